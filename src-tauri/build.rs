@@ -1,3 +1,70 @@
+use std::{env, fs, io, path::Path};
+
+use sha2::{Digest, Sha256};
+
+const MANIFEST_FILE_NAME: &str = "SHA256SUMS";
+
 fn main() {
+    write_bundled_checksums();
     tauri_build::build()
 }
+
+//the pinned manifest is reviewed together with the resources, so a replaced
+//blob fails the build instead of getting a fresh checksum generated for it
+fn write_bundled_checksums() {
+    println!("cargo:rerun-if-changed=resources");
+
+    let manifest_path = Path::new("resources").join(MANIFEST_FILE_NAME);
+    let content =
+        fs::read_to_string(&manifest_path).expect("Failed to read bundled resources manifest");
+
+    let mut checksums: Vec<(String, String)> = content
+        .lines()
+        .filter_map(|line| line.split_once(char::is_whitespace))
+        .map(|(hash, name)| {
+            (
+                name.trim().trim_start_matches('*').to_string(),
+                hash.to_lowercase(),
+            )
+        })
+        .collect();
+
+    if let Ok(entries) = fs::read_dir("resources") {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            if !path.is_file() || file_name == MANIFEST_FILE_NAME {
+                continue;
+            }
+
+            let mut file = fs::File::open(&path).expect("Failed to open bundled resource");
+            let mut hasher = Sha256::new();
+            io::copy(&mut file, &mut hasher).expect("Failed to hash bundled resource");
+            let actual = hex::encode(hasher.finalize());
+
+            match checksums.iter().find(|(name, _)| *name == file_name) {
+                Some((_, expected)) if *expected == actual => {}
+                Some((_, expected)) => panic!(
+                    "Bundled resource {} doesn't match {}: expected {}, got {}",
+                    file_name, MANIFEST_FILE_NAME, expected, actual
+                ),
+                None => panic!(
+                    "Bundled resource {} is missing from {}",
+                    file_name, MANIFEST_FILE_NAME
+                ),
+            }
+        }
+    }
+
+    checksums.sort();
+
+    let mut manifest = String::from("pub const BUNDLED_CHECKSUMS: &[(&str, &str)] = &[\n");
+    for (file_name, checksum) in checksums {
+        manifest.push_str(&format!("    ({:?}, {:?}),\n", file_name, checksum));
+    }
+    manifest.push_str("];\n");
+
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR not set");
+    fs::write(Path::new(&out_dir).join("bundled_checksums.rs"), manifest)
+        .expect("Failed to write bundled checksums manifest");
+}
@@ -8,13 +8,33 @@ use tauri::path::BaseDirectory;
 use tauri::Manager;
 use tauri_plugin_log;
 
+use crate::setup::environment::cancel_setup;
 use crate::setup::environment::setup_environment;
+use crate::utility::cache::clear_download_cache;
+use crate::utility::cache::get_download_cache;
 use crate::utility::launcher::check_config;
 use crate::utility::launcher::launch_game;
 use crate::utility::launcher::stop_game;
+use crate::utility::runner::get_dxvk_releases;
+use crate::utility::runner::get_runner_releases;
+use crate::utility::runner::install_dxvk;
+use crate::utility::runner::install_runner;
 use crate::utility::setting::add_wine_version;
+use crate::utility::setting::get_prefix_dxvk_state;
+use crate::utility::setting::get_prefix_settings;
 use crate::utility::setting::populate_settings;
+use crate::utility::setting::remove_dxvk_from_prefix;
+use crate::utility::setting::remove_dxvk_version;
+use crate::utility::setting::remove_wine_version;
 use crate::utility::setting::save_settings;
+use crate::utility::setting::set_prefix_settings;
+use crate::utility::snapshot::create_prefix_snapshot;
+use crate::utility::snapshot::delete_prefix_snapshot;
+use crate::utility::snapshot::list_prefix_snapshots;
+use crate::utility::snapshot::restore_prefix_snapshot;
+use crate::utility::winetricks::cancel_winetricks;
+use crate::utility::winetricks::get_winetricks_verbs;
+use crate::utility::winetricks::install_winetricks_verbs;
 use crate::utils::initialize::get_command_availability;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -46,13 +66,33 @@ pub fn run() {
         })
         .invoke_handler(tauri::generate_handler![
             setup_environment,
+            cancel_setup,
             check_config,
             launch_game,
             stop_game,
             populate_settings,
             save_settings,
             get_command_availability,
-            add_wine_version
+            add_wine_version,
+            get_prefix_dxvk_state,
+            get_prefix_settings,
+            set_prefix_settings,
+            remove_dxvk_from_prefix,
+            get_download_cache,
+            clear_download_cache,
+            get_runner_releases,
+            install_runner,
+            get_dxvk_releases,
+            install_dxvk,
+            remove_wine_version,
+            remove_dxvk_version,
+            get_winetricks_verbs,
+            install_winetricks_verbs,
+            cancel_winetricks,
+            create_prefix_snapshot,
+            list_prefix_snapshots,
+            restore_prefix_snapshot,
+            delete_prefix_snapshot
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -1,22 +1,420 @@
-use std::path::{Path, PathBuf};
+use std::{
+    collections::BTreeSet,
+    ffi::{OsStr, OsString},
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
 
-use log::info;
+use log::{info, warn};
 
-use crate::{types::error::CustomError, utils::fs::create_symlinks_by_filename};
+use crate::{
+    setup::validate::check_dxvk_symlinks,
+    types::{
+        config::{D3D9Backend, DxvkInstallMode},
+        error::CustomError,
+        prefix::PrefixDxvkState,
+    },
+    utils::{
+        checksum::sha256_file,
+        fs::{copy_files_by_filename, create_symlinks_by_filename},
+        process::run_command,
+        runner::resolve_wine_binary,
+    },
+};
 
-pub fn setup_dxvk(wine_prefix: &str, dxvk_path: &Path) -> Result<(), CustomError> {
+//wine's own dlls are kept here before dxvk replaces them
+const DXVK_BACKUP_DIR: &str = "sbrw-dxvk-backup";
+const DXVK_STATE_FILE: &str = "sbrw-dxvk.json";
+//prefix folder and the dxvk folder installed into it
+const DXVK_ARCH_DIRS: [(&str, &str); 2] = [("system32", "x64"), ("syswow64", "x32")];
+//wine marks its own pe dlls right after the dos header
+const WINE_DLL_SIGNATURE_OFFSET: usize = 0x40;
+const WINE_DLL_SIGNATURES: [&[u8]; 2] = [b"Wine builtin DLL", b"Wine placeholder DLL"];
+
+//the dxvk copies proton leaves in the prefix are native dlls, not wine's
+fn has_wine_signature(dll_path: &Path) -> bool {
+    let mut header = [0u8; WINE_DLL_SIGNATURE_OFFSET + 20];
+
+    fs::File::open(dll_path)
+        .and_then(|mut file| file.read_exact(&mut header))
+        .is_ok()
+        && WINE_DLL_SIGNATURES
+            .iter()
+            .any(|signature| header[WINE_DLL_SIGNATURE_OFFSET..].starts_with(signature))
+}
+
+fn is_builtin_dll(dll_path: &Path, installed_sources: &[PathBuf]) -> bool {
+    //links and copies are from an earlier dxvk setup
+    fs::symlink_metadata(dll_path).is_ok_and(|meta| meta.is_file())
+        && has_wine_signature(dll_path)
+        && !installed_sources
+            .iter()
+            .any(|source_file| is_installed_from(dll_path, source_file))
+}
+
+//keeps wine's dll once, an existing backup already holds the original
+pub fn backup_builtin_dll(
+    wine_prefix: &str,
+    windows_sub_dir: &str,
+    file_name: &OsStr,
+    installed_sources: &[PathBuf],
+) -> Result<(), CustomError> {
+    let dll_path = PathBuf::from(wine_prefix)
+        .join("drive_c/windows")
+        .join(windows_sub_dir)
+        .join(file_name);
+    let backup_file_path = builtin_backup_path(wine_prefix, windows_sub_dir, file_name);
+
+    if is_builtin_dll(&dll_path, installed_sources) && !backup_file_path.exists() {
+        if let Some(backup_dir) = backup_file_path.parent() {
+            fs::create_dir_all(backup_dir)?;
+        }
+        fs::copy(&dll_path, &backup_file_path)?;
+    }
+
+    Ok(())
+}
+
+pub fn builtin_backup_path(wine_prefix: &str, windows_sub_dir: &str, file_name: &OsStr) -> PathBuf {
+    PathBuf::from(wine_prefix)
+        .join(DXVK_BACKUP_DIR)
+        .join(windows_sub_dir)
+        .join(file_name)
+}
+
+//the dxvk build the prefix currently has installed, if any
+pub fn installed_dxvk_path(wine_prefix: &str) -> Option<PathBuf> {
+    let state = read_dxvk_state(wine_prefix);
+    state.path.filter(|_| state.installed).map(PathBuf::from)
+}
+
+pub fn setup_dxvk(
+    wine_prefix: &str,
+    dxvk_path: &Path,
+    install_mode: DxvkInstallMode,
+    d3d9_backend: D3D9Backend,
+) -> Result<(), CustomError> {
     if !dxvk_path.exists() {
         return Err(CustomError::PathError("DXVK path not found".to_string()));
     }
 
-    //setting up dxvk symlinks in system32 and syswow64 directories
+    //setting up dxvk dlls in system32 and syswow64 directories
+    let windows_dir = PathBuf::from(wine_prefix).join("drive_c/windows");
+    let installed_path = installed_dxvk_path(wine_prefix);
+    let excluded = d3d9_backend.dxvk_excluded_dlls();
+
+    for (windows_sub_dir, dxvk_sub_dir) in DXVK_ARCH_DIRS {
+        let source_dir = dxvk_path.join(dxvk_sub_dir);
+
+        for name in dll_names(&source_dir) {
+            if excluded.iter().any(|dll| name == *dll) {
+                continue;
+            }
+
+            let mut installed_sources = vec![source_dir.join(&name)];
+            if let Some(installed_path) = &installed_path {
+                installed_sources.push(installed_path.join(dxvk_sub_dir).join(&name));
+            }
+            backup_builtin_dll(wine_prefix, windows_sub_dir, &name, &installed_sources)?;
+        }
+    }
+
+    for (windows_sub_dir, dxvk_sub_dir) in DXVK_ARCH_DIRS {
+        let source_dir = dxvk_path.join(dxvk_sub_dir);
+        let dest_dir = windows_dir.join(windows_sub_dir);
+
+        match install_mode {
+            DxvkInstallMode::Symlink => {
+                create_symlinks_by_filename(&source_dir, &dest_dir, true, excluded)?
+            }
+            DxvkInstallMode::Copy => copy_files_by_filename(&source_dir, &dest_dir, excluded)?,
+        }
+
+        //dlls left to another backend go back to wine's, unless that backend already took over
+        for dll in excluded {
+            let dll_path = dest_dir.join(dll);
+            let is_dxvk = is_dxvk_symlink(&dll_path, dxvk_sub_dir)
+                || is_installed_from(&dll_path, &source_dir.join(dll))
+                || installed_path.as_ref().is_some_and(|installed_path| {
+                    is_installed_from(&dll_path, &installed_path.join(dxvk_sub_dir).join(dll))
+                });
+            if is_dxvk {
+                restore_builtin_dll(
+                    &dll_path,
+                    &builtin_backup_path(wine_prefix, windows_sub_dir, OsStr::new(dll)),
+                )?;
+            }
+        }
+    }
+
+    write_dxvk_state(wine_prefix, Some(dxvk_path), install_mode)?;
+
+    info!("dxvk {:?} setup completed", install_mode);
+
+    Ok(())
+}
+
+fn dll_names(dir: &Path) -> BTreeSet<OsString> {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().is_file())
+                .map(|entry| entry.file_name())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+//a link to the source file, or a copy of it
+fn is_installed_from(dll_path: &Path, source_file: &Path) -> bool {
+    if dll_path.is_symlink() {
+        return fs::read_link(dll_path).is_ok_and(|target| target == source_file);
+    }
+
+    dll_path.is_file()
+        && source_file.is_file()
+        && matches!(
+            (sha256_file(dll_path), sha256_file(source_file)),
+            (Ok(installed), Ok(source)) if installed == source
+        )
+}
+
+//puts wine's dll back if there is a backup of it, false when wineboot has to recreate it
+pub fn restore_builtin_dll(dll_path: &Path, backup_file_path: &Path) -> Result<bool, CustomError> {
+    if fs::symlink_metadata(dll_path).is_ok() {
+        fs::remove_file(dll_path)?;
+    }
+
+    if !backup_file_path.is_file() {
+        return Ok(false);
+    }
+
+    fs::rename(backup_file_path, dll_path)?;
+
+    Ok(true)
+}
+
+//removes dlls installed from a dxvk build, except those the kept build ships as well
+fn remove_dxvk_dlls(
+    wine_prefix: &str,
+    dxvk_path: &Path,
+    keep_path: Option<&Path>,
+) -> Result<(), CustomError> {
+    let windows_dir = PathBuf::from(wine_prefix).join("drive_c/windows");
+    let backup_dir = PathBuf::from(wine_prefix).join(DXVK_BACKUP_DIR);
+    let mut removed = 0;
+
+    for (windows_sub_dir, dxvk_sub_dir) in DXVK_ARCH_DIRS {
+        let source_dir = dxvk_path.join(dxvk_sub_dir);
+        let kept = keep_path
+            .map(|path| dll_names(&path.join(dxvk_sub_dir)))
+            .unwrap_or_default();
+
+        for name in dll_names(&source_dir).difference(&kept) {
+            let dll_path = windows_dir.join(windows_sub_dir).join(name);
+            if is_installed_from(&dll_path, &source_dir.join(name)) {
+                restore_builtin_dll(&dll_path, &backup_dir.join(windows_sub_dir).join(name))?;
+                removed += 1;
+            }
+        }
+    }
+
+    if removed > 0 {
+        info!("Removed {} leftover dlls of {:?}", removed, dxvk_path);
+    }
+
+    Ok(())
+}
+
+pub fn switch_dxvk(
+    wine_prefix: &str,
+    previous_path: &Path,
+    dxvk_path: &Path,
+    install_mode: DxvkInstallMode,
+    d3d9_backend: D3D9Backend,
+) -> Result<(), CustomError> {
+    let previous_state = read_dxvk_state(wine_prefix);
+
+    let result = setup_dxvk(wine_prefix, dxvk_path, install_mode, d3d9_backend).and_then(|_| {
+        check_dxvk_symlinks(
+            dxvk_path,
+            Path::new(wine_prefix),
+            install_mode,
+            d3d9_backend,
+        )
+    });
+
+    if let Err(err) = result {
+        //putting the previous state back so the prefix never ends up with a mix of both
+        warn!(
+            "Switching dxvk failed, restoring {:?}: {}",
+            previous_path, err
+        );
+        remove_dxvk_dlls(wine_prefix, dxvk_path, None)?;
+        if previous_state.installed && previous_path.exists() {
+            setup_dxvk(
+                wine_prefix,
+                previous_path,
+                previous_state.install_mode,
+                d3d9_backend,
+            )?;
+        } else {
+            write_dxvk_state(wine_prefix, None, previous_state.install_mode)?;
+        }
+        return Err(err);
+    }
+
+    if previous_path != dxvk_path {
+        remove_dxvk_dlls(wine_prefix, previous_path, Some(dxvk_path))?;
+    }
+
+    info!("Switched dxvk from {:?} to {:?}", previous_path, dxvk_path);
+
+    Ok(())
+}
+
+//a dll links to dxvk when it points into some x64/x32 folder, wine's own links never do
+fn is_dxvk_symlink(dll_path: &Path, dxvk_sub_dir: &str) -> bool {
+    dll_path.is_symlink()
+        && fs::read_link(dll_path).is_ok_and(|target| {
+            target
+                .parent()
+                .is_some_and(|parent| parent.ends_with(dxvk_sub_dir))
+        })
+}
+
+pub fn uninstall_dxvk(wine_prefix: &str, wine_path: &Path) -> Result<(), CustomError> {
     let windows_dir = PathBuf::from(wine_prefix).join("drive_c/windows");
+    let backup_dir = PathBuf::from(wine_prefix).join(DXVK_BACKUP_DIR);
+    let state = read_dxvk_state(wine_prefix);
+    let mut missing_backups = 0;
 
-    create_symlinks_by_filename(&dxvk_path.join("x32"), &windows_dir.join("syswow64"), true)?;
+    for (windows_sub_dir, dxvk_sub_dir) in DXVK_ARCH_DIRS {
+        let dll_dir = windows_dir.join(windows_sub_dir);
+        let arch_backup_dir = backup_dir.join(windows_sub_dir);
+        let source_dir = state
+            .path
+            .as_ref()
+            .map(|path| Path::new(path).join(dxvk_sub_dir));
 
-    create_symlinks_by_filename(&dxvk_path.join("x64"), &windows_dir.join("system32"), true)?;
+        //copies only show up through the backups or the recorded build
+        let mut names = dll_names(&arch_backup_dir);
+        if let Some(source_dir) = &source_dir {
+            names.extend(dll_names(source_dir));
+        }
+        for entry in fs::read_dir(&dll_dir)? {
+            let dll_path = entry?.path();
+            if is_dxvk_symlink(&dll_path, dxvk_sub_dir) {
+                names.extend(dll_path.file_name().map(|name| name.to_os_string()));
+            }
+        }
 
-    info!("dxvk symlinks setup completed");
+        for name in names {
+            let dll_path = dll_dir.join(&name);
+            let backup_file_path = arch_backup_dir.join(&name);
+
+            //other links, e.g. gallium nine's d3d9.dll, belong to someone else
+            let is_dxvk = if dll_path.is_symlink() {
+                is_dxvk_symlink(&dll_path, dxvk_sub_dir)
+            } else {
+                backup_file_path.is_file()
+                    || source_dir.as_ref().is_some_and(|source_dir| {
+                        is_installed_from(&dll_path, &source_dir.join(&name))
+                    })
+            };
+            if !is_dxvk {
+                continue;
+            }
+
+            if !restore_builtin_dll(&dll_path, &backup_file_path)? {
+                missing_backups += 1;
+            }
+        }
+    }
+
+    //prefixes set up before backups existed get their dlls back from wine itself
+    if missing_backups > 0 {
+        info!(
+            "{} dlls have no backup, restoring them with wineboot",
+            missing_backups
+        );
+        run_command(
+            resolve_wine_binary(wine_path),
+            Some(&["wineboot", "-u"]),
+            Some(&[("WINEPREFIX", wine_prefix)]),
+            None,
+            None,
+        )?;
+    }
+
+    //backups of dlls another backend still replaces stay around for later
+    for (windows_sub_dir, _) in DXVK_ARCH_DIRS {
+        fs::remove_dir(backup_dir.join(windows_sub_dir)).ok();
+    }
+    fs::remove_dir(&backup_dir).ok();
+
+    write_dxvk_state(wine_prefix, None, state.install_mode)?;
+
+    info!("dxvk removed from prefix {}", wine_prefix);
+
+    Ok(())
+}
+
+fn write_dxvk_state(
+    wine_prefix: &str,
+    dxvk_path: Option<&Path>,
+    install_mode: DxvkInstallMode,
+) -> Result<(), CustomError> {
+    let state = PrefixDxvkState {
+        installed: dxvk_path.is_some(),
+        version: dxvk_path
+            .and_then(|path| path.file_name())
+            .map(|name| name.to_string_lossy().into_owned()),
+        path: dxvk_path.map(|path| path.display().to_string()),
+        install_mode,
+        updated_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+    };
+
+    fs::write(
+        Path::new(wine_prefix).join(DXVK_STATE_FILE),
+        serde_json::to_string_pretty(&state)?,
+    )?;
 
     Ok(())
 }
+
+pub fn read_dxvk_state(wine_prefix: &str) -> PrefixDxvkState {
+    let recorded = fs::read_to_string(Path::new(wine_prefix).join(DXVK_STATE_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok());
+    if let Some(state) = recorded {
+        return state;
+    }
+
+    //prefixes from older versions have no state file, the d3d11 link tells the same
+    let d3d11 = Path::new(wine_prefix).join("drive_c/windows/system32/d3d11.dll");
+    if !is_dxvk_symlink(&d3d11, "x64") {
+        return PrefixDxvkState::default();
+    }
+
+    let dxvk_path = fs::read_link(&d3d11)
+        .ok()
+        .and_then(|target| target.parent()?.parent().map(Path::to_path_buf));
+
+    PrefixDxvkState {
+        installed: true,
+        version: dxvk_path
+            .as_ref()
+            .and_then(|path| path.file_name())
+            .map(|name| name.to_string_lossy().into_owned()),
+        path: dxvk_path.map(|path| path.display().to_string()),
+        install_mode: DxvkInstallMode::Symlink,
+        updated_at: 0,
+    }
+}
@@ -1,21 +1,72 @@
-use log::info;
+use log::{info, warn};
 use std::fs;
+use std::path::Path;
 use tauri::path::BaseDirectory;
 use tauri::Window;
 use tauri::{AppHandle, Manager};
 
 use crate::setup::dxvk::setup_dxvk;
 use crate::setup::finalize::finalize_setup;
+use crate::setup::journal::{clear_journal, load_journal, save_journal};
+use crate::setup::nine::setup_nine;
 use crate::setup::resource::{
     download_game_launcher, extract_bundled_archives, write_bundled_resources,
 };
-use crate::setup::validate::validate_installation;
-use crate::setup::wine::{initialize_wine_environment, install_runtimes};
+use crate::setup::validate::{
+    check_bundled_resources, check_dxvk_symlinks, check_game_launcher_exe, check_mono_runtime,
+    check_regedit_import, check_vc_runtimes, check_wine_bin_dir, check_wine_prefix,
+    validate_installation,
+};
+use crate::setup::wine::{initialize_wine_environment, install_runtimes, stop_wine_prefix};
+use crate::types::config::D3D9Backend;
 use crate::types::error::CustomError;
-use crate::types::setup::SetupOptions;
+use crate::types::setup::{SetupJournal, SetupOptions};
 use crate::types::wizard::WizardStep;
+use crate::utils::cancel::{cancel_setup_token, new_setup_token, CancellationToken};
 use crate::utils::emitter::report_step;
-use crate::utils::fs::cleanup_dir;
+use crate::utils::fs::{cleanup_dir, cleanup_dir_except};
+use crate::utils::initialize::{
+    get_config, get_d3d9_settings, get_dxvk_install_mode, get_network_settings, update_config,
+};
+use crate::utils::runner::resolve_wineserver;
+
+const WINE_VERSION: &str = "wine-10.8-staging-amd64";
+const DXVK_VERSION: &str = "dxvk-1.10.3";
+const MONO_VERSION: &str = "wine-mono-10.0.0-x86.msi";
+
+struct StepTracker<'a> {
+    window: &'a Window,
+    app_dir: &'a Path,
+    journal: SetupJournal,
+    resuming: bool,
+    token: &'a CancellationToken,
+}
+
+impl<'a> StepTracker<'a> {
+    //reports the step and tells whether it has to run, a step a previous run finished
+    //is skipped as long as its own outputs are still in place
+    fn begin(
+        &mut self,
+        step: WizardStep,
+        outputs_valid: impl FnOnce(&SetupJournal) -> bool,
+    ) -> Result<bool, CustomError> {
+        self.token.check()?;
+
+        report_step(self.window, step.clone())?;
+
+        if self.resuming && self.journal.is_completed(&step) && outputs_valid(&self.journal) {
+            info!("Skipping {:?}, already completed by a previous run", step);
+            return Ok(false);
+        }
+
+        Ok(true)
+    }
+
+    fn complete(&mut self, step: WizardStep) -> Result<(), CustomError> {
+        self.journal.mark_completed(step);
+        save_journal(self.app_dir, &self.journal)
+    }
+}
 
 #[tauri::command]
 pub fn setup_environment(
@@ -23,89 +74,233 @@ pub fn setup_environment(
     window: Window,
     app: AppHandle,
 ) -> Result<(), CustomError> {
+    let token = new_setup_token()?;
+
     std::thread::spawn(move || -> Result<(), CustomError> {
         info!("Setup started");
 
         //Get the app dir which is ~/.local/share/sbrw-utility
         let app_dir = app.path().resolve("sbrw-utility", BaseDirectory::Data)?;
 
-        report_step(&window, WizardStep::Cleanup)?;
-        cleanup_dir(&app_dir, "app")?;
+        match run_setup(&options, &window, &app_dir, &token) {
+            Err(CustomError::Cancelled) => {
+                //the journal only holds finished steps, so stopping the prefix's
+                //wine processes is enough to leave it resumable
+                let wineserver_path = resolve_wineserver(
+                    &app_dir.join("runners/wine").join(WINE_VERSION).join("bin"),
+                );
+                stop_wine_prefix(&wineserver_path, options.wine_prefix())
+                    .inspect_err(|e| warn!("Failed to stop the prefix after cancelling: {}", e))
+                    .ok();
 
-        report_step(&window, WizardStep::SetupFolders)?;
+                report_step(&window, WizardStep::Cancelled)?;
+                info!("Setup cancelled");
 
-        let wine_version = "wine-10.8-staging-amd64";
-        let dxvk_version = "dxvk-1.10.3";
-        let mono_version = "wine-mono-10.0.0-x86.msi";
+                Ok(())
+            }
+            result => result,
+        }
+    });
 
-        //Creating necessary directories
-        let runners_dir = app_dir.join("runners");
-        let runtime_dir = app_dir.join("runtime");
-        let tools_dir = app_dir.join("tools");
+    Ok(())
+}
 
-        let wine_prefix = options.wine_prefix();
-        let runners_wine_dir = runners_dir.join("wine");
-        let runners_proton_dir = runners_dir.join("proton");
-        let runtime_dxvk_dir = runtime_dir.join("dxvk");
+#[tauri::command]
+pub fn cancel_setup() -> Result<(), CustomError> {
+    info!("Setup cancellation requested");
+
+    //commands started by the setup poll its token and kill themselves
+    cancel_setup_token()?;
 
-        let wine_path = runners_wine_dir.join(wine_version);
-        let dxvk_path = runtime_dxvk_dir.join(dxvk_version);
-        let mono_msi_path = tools_dir.join(mono_version);
+    Ok(())
+}
 
+fn run_setup(
+    options: &SetupOptions,
+    window: &Window,
+    app_dir: &Path,
+    token: &CancellationToken,
+) -> Result<(), CustomError> {
+    let wine_prefix = options.wine_prefix();
+    //a re-setup without network settings from the wizard keeps the configured ones
+    let network = options
+        .network()
+        .cloned()
+        .unwrap_or_else(get_network_settings);
+
+    //a journal is only reused for the same prefix it was written for
+    let previous_journal = if options.resume() {
+        load_journal(app_dir)?.filter(|journal| journal.wine_prefix == wine_prefix)
+    } else {
+        None
+    };
+
+    let resuming = previous_journal.is_some();
+
+    let mut tracker = StepTracker {
+        window,
+        app_dir,
+        journal: previous_journal.unwrap_or_else(|| SetupJournal::new(wine_prefix)),
+        resuming,
+        token,
+    };
+
+    report_step(window, WizardStep::Cleanup)?;
+    if resuming {
+        info!("Resuming previous setup, skipping cleanup");
+    } else {
+        //the download cache is kept so a re-setup can reuse earlier downloads
+        cleanup_dir_except(app_dir, "app", &["cache"])?;
+    }
+
+    let wine_version = WINE_VERSION;
+    let dxvk_version = DXVK_VERSION;
+    let mono_version = MONO_VERSION;
+
+    //Creating necessary directories
+    let runners_dir = app_dir.join("runners");
+    let runtime_dir = app_dir.join("runtime");
+    let tools_dir = app_dir.join("tools");
+
+    let wine_prefix_path = Path::new(wine_prefix);
+    let runners_wine_dir = runners_dir.join("wine");
+    let runners_proton_dir = runners_dir.join("proton");
+    let runtime_dxvk_dir = runtime_dir.join("dxvk");
+
+    let wine_path = runners_wine_dir.join(wine_version);
+    let dxvk_path = runtime_dxvk_dir.join(dxvk_version);
+    let mono_msi_path = tools_dir.join(mono_version);
+
+    if tracker.begin(WizardStep::SetupFolders, |_| {
+        runners_wine_dir.is_dir()
+            && runners_proton_dir.is_dir()
+            && runtime_dxvk_dir.is_dir()
+            && tools_dir.is_dir()
+    })? {
         fs::create_dir_all(&runners_wine_dir)?;
         fs::create_dir_all(&runners_proton_dir)?;
         fs::create_dir_all(&runtime_dxvk_dir)?;
         fs::create_dir_all(&tools_dir)?;
+        tracker.complete(WizardStep::SetupFolders)?;
+    }
 
-        report_step(&window, WizardStep::DownloadLauncher)?;
-        let launcher_path = download_game_launcher(&tools_dir)?;
+    if tracker.begin(WizardStep::DownloadLauncher, |journal| {
+        journal
+            .launcher_path
+            .as_ref()
+            .is_some_and(|path| path.is_file())
+    })? {
+        let launcher_path = download_game_launcher(&tools_dir, &network, window, Some(token))?;
+        tracker.journal.launcher_path = Some(launcher_path);
+        tracker.complete(WizardStep::DownloadLauncher)?;
+    }
 
-        report_step(&window, WizardStep::WriteResources)?;
+    if tracker.begin(WizardStep::WriteResources, |_| {
+        check_bundled_resources(&tools_dir).is_ok()
+    })? {
         write_bundled_resources(&tools_dir)?;
+        tracker.complete(WizardStep::WriteResources)?;
+    }
 
-        report_step(&window, WizardStep::ExtractResources)?;
-        extract_bundled_archives(&tools_dir, &runners_wine_dir, &runtime_dxvk_dir)?;
+    if tracker.begin(WizardStep::ExtractResources, |_| {
+        check_wine_bin_dir(&wine_path.join("bin")).is_ok()
+            && dxvk_path.join("x32").is_dir()
+            && dxvk_path.join("x64").is_dir()
+    })? {
+        extract_bundled_archives(&tools_dir, &runners_wine_dir, &runtime_dxvk_dir, window)?;
+        tracker.complete(WizardStep::ExtractResources)?;
+    }
 
-        report_step(&window, WizardStep::InitializeWine)?;
-        initialize_wine_environment(&wine_path.join("bin/wineboot"), &wine_prefix)?;
+    if tracker.begin(WizardStep::InitializeWine, |_| {
+        check_wine_prefix(wine_prefix_path).is_ok()
+    })? {
+        initialize_wine_environment(&wine_path.join("bin/wineboot"), wine_prefix, token)?;
+        tracker.complete(WizardStep::InitializeWine)?;
+    }
 
-        report_step(&window, WizardStep::InstallRuntimes)?;
+    if tracker.begin(WizardStep::InstallRuntimes, |_| {
+        check_mono_runtime(wine_prefix_path).is_ok()
+            && check_vc_runtimes(wine_prefix_path).is_ok()
+            && check_regedit_import(wine_prefix_path, &tools_dir.join("overrides.reg")).is_ok()
+    })? {
         install_runtimes(
             &mono_msi_path,
             &wine_path.join("bin/wine"),
-            &wine_prefix,
+            wine_prefix,
             &tools_dir,
+            token,
+        )?;
+        tracker.complete(WizardStep::InstallRuntimes)?;
+    }
+
+    if tracker.begin(WizardStep::SetupDXVK, |_| {
+        check_dxvk_symlinks(
+            &dxvk_path,
+            wine_prefix_path,
+            get_dxvk_install_mode(),
+            get_d3d9_settings().backend,
+        )
+        .is_ok()
+    })? {
+        setup_dxvk(
+            wine_prefix,
+            &dxvk_path,
+            get_dxvk_install_mode(),
+            get_d3d9_settings().backend,
         )?;
 
-        report_step(&window, WizardStep::SetupDXVK)?;
-        setup_dxvk(&wine_prefix, &dxvk_path)?;
+        //nine is optional during setup, startup validation repairs it later
+        let d3d9 = get_d3d9_settings();
+        if d3d9.backend == D3D9Backend::Nine && !d3d9.nine_path.is_empty() {
+            if let Err(e) = setup_nine(wine_prefix, Path::new(&d3d9.nine_path)) {
+                warn!("Failed to set up Gallium Nine: {}", e);
+            }
+        }
+        tracker.complete(WizardStep::SetupDXVK)?;
+    }
+
+    if tracker.begin(WizardStep::FinalizeSetup, |_| {
+        check_game_launcher_exe(wine_prefix_path).is_ok() && app_dir.join("config.json").is_file()
+    })? {
+        let launcher_path = tracker.journal.launcher_path.clone().ok_or_else(|| {
+            CustomError::PathError("Downloaded game launcher not found".to_string())
+        })?;
 
-        report_step(&window, WizardStep::FinalizeSetup)?;
         finalize_setup(
-            &wine_prefix,
+            wine_prefix,
             &launcher_path,
-            &app_dir,
-            &wine_version,
+            app_dir,
+            wine_version,
             &wine_path.join("bin"),
-            &dxvk_version,
+            dxvk_version,
             &dxvk_path,
         )?;
 
-        report_step(&window, WizardStep::ValidateInstallation)?;
-        validate_installation(&wine_prefix, &wine_path.join("bin"), &dxvk_path).map_err(
-            |error| {
-                report_step(&window, WizardStep::Failed).ok();
-                info!("Installation validation failed {0}", error);
-                error
-            },
-        )?;
-        info!("Validation successful, everything's in place");
+        //the wizard's network settings become the configured ones
+        if let Some(network) = options.network() {
+            let mut config = get_config()?;
+            config.network = network.clone();
+            update_config(config, &app_dir.join("config.json"))?;
+        }
+        tracker.complete(WizardStep::FinalizeSetup)?;
+    }
 
-        report_step(&window, WizardStep::Complete)?;
-        info!("Setup completed successfully");
+    report_step(window, WizardStep::ValidateInstallation)?;
+    validate_installation(wine_prefix, &wine_path.join("bin"), &dxvk_path, true).map_err(
+        |error| {
+            report_step(window, WizardStep::Failed).ok();
+            info!("Installation validation failed {0}", error);
+            error
+        },
+    )?;
+    info!("Validation successful, everything's in place");
 
-        Ok(())
-    });
+    //the tools stay until here so a failed validation can be resumed without downloading again
+    cleanup_dir(&tools_dir, "tools")?;
+    clear_journal(app_dir)?;
+
+    report_step(window, WizardStep::Complete)?;
+    info!("Setup completed successfully");
 
     Ok(())
 }
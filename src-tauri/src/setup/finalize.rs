@@ -10,12 +10,13 @@ use tauri::AppHandle;
 
 use crate::{
     types::{
+        archive::ExtractOptions,
         config::{Config, DXVKComponent, GameComponent, KeyValue, WineComponent},
         error::CustomError,
     },
     utils::{
-        fs::{cleanup_dir, extract_archive},
-        initialize::init_config,
+        archive::extract_archive,
+        initialize::{get_config, get_dxvk_install_mode, init_config},
     },
 };
 
@@ -24,7 +25,13 @@ pub fn extract_to_game_dir(game_dir: &Path, launcher_path: &Path) -> Result<(),
     fs::create_dir_all(&game_dir)?;
 
     //extracting the downloaded game launcher
-    extract_archive(&launcher_path, &game_dir)?;
+    //the launcher zip has its files at the top level
+    extract_archive(
+        &launcher_path,
+        &game_dir,
+        &ExtractOptions::default(),
+        &mut |_| {},
+    )?;
 
     info!("Extracted game launcher to drive_c/SBRW");
 
@@ -67,6 +74,7 @@ fn write_config(
         version: dxvk_version.to_string(),
         path: dxvk_path.display().to_string(),
         enabled: true,
+        install_mode: get_dxvk_install_mode(),
     };
 
     let environment_variables: Vec<KeyValue> = Vec::new();
@@ -83,9 +91,21 @@ fn write_config(
         mangohud: false,
         environment_variables: environment_variables,
         dll_overrides: dll_overrides,
+        persist_dll_overrides: false,
     };
 
-    let config = Config::new(wine, dxvk, game);
+    let mut config = Config::new(wine, dxvk, game);
+
+    //keeping the user's cache, network, runner source, search path, d3d9 and
+    //dll override persistence settings across re-setups
+    if let Ok(previous_config) = get_config() {
+        config.cache = previous_config.cache;
+        config.network = previous_config.network;
+        config.runners = previous_config.runners;
+        config.search_paths = previous_config.search_paths;
+        config.d3d9 = previous_config.d3d9;
+        config.game.persist_dll_overrides = previous_config.game.persist_dll_overrides;
+    }
 
     let json_string = serde_json::to_string_pretty(&config)?;
 
@@ -96,15 +116,6 @@ fn write_config(
     Ok(())
 }
 
-fn cleanup_setup(app_dir: &Path) -> Result<(), CustomError> {
-    let tools_dir = app_dir.join("tools");
-
-    //cleaning up tools folder
-    cleanup_dir(&tools_dir, "tools")?;
-
-    Ok(())
-}
-
 pub fn finalize_setup(
     wine_prefix: &str,
     launcher_path: &Path,
@@ -133,7 +144,5 @@ pub fn finalize_setup(
 
     init_config(app_dir)?;
 
-    cleanup_setup(app_dir)?;
-
     Ok(())
 }
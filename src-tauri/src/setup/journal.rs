@@ -0,0 +1,50 @@
+use std::{fs, path::Path};
+
+use log::{info, warn};
+
+use crate::{
+    types::{error::CustomError, setup::SetupJournal},
+    utils::fs::cleanup_file,
+};
+
+const JOURNAL_FILE_NAME: &str = "setup-journal.json";
+
+pub fn load_journal(app_dir: &Path) -> Result<Option<SetupJournal>, CustomError> {
+    let journal_path = app_dir.join(JOURNAL_FILE_NAME);
+
+    if !journal_path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(&journal_path)?;
+
+    //a corrupt journal only means the setup can't be resumed
+    match serde_json::from_str::<SetupJournal>(&content) {
+        Ok(journal) => Ok(Some(journal)),
+        Err(err) => {
+            warn!("Ignoring unreadable setup journal: {}", err);
+            Ok(None)
+        }
+    }
+}
+
+pub fn save_journal(app_dir: &Path, journal: &SetupJournal) -> Result<(), CustomError> {
+    fs::create_dir_all(app_dir)?;
+
+    let json_string = serde_json::to_string_pretty(journal)?;
+
+    //write to a temporary file first so a crash never leaves a half written journal
+    let tmp_path = app_dir.join(format!("{}.tmp", JOURNAL_FILE_NAME));
+    fs::write(&tmp_path, json_string)?;
+    fs::rename(&tmp_path, app_dir.join(JOURNAL_FILE_NAME))?;
+
+    Ok(())
+}
+
+pub fn clear_journal(app_dir: &Path) -> Result<(), CustomError> {
+    cleanup_file(&app_dir.join(JOURNAL_FILE_NAME))?;
+
+    info!("Setup journal cleared");
+
+    Ok(())
+}
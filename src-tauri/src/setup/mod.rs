@@ -1,6 +1,9 @@
 pub mod dxvk;
 pub mod environment;
 pub mod finalize;
+pub mod journal;
+pub mod nine;
+pub mod prefix;
 pub mod resource;
 pub mod validate;
 pub mod wine;
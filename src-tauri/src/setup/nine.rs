@@ -0,0 +1,140 @@
+use std::{
+    ffi::OsStr,
+    fs,
+    io::Read,
+    os::unix::fs as unix_fs,
+    path::{Path, PathBuf},
+};
+
+use log::info;
+
+use crate::{
+    setup::{
+        dxvk::{backup_builtin_dll, builtin_backup_path, installed_dxvk_path, restore_builtin_dll},
+        validate::check_nine,
+    },
+    types::error::CustomError,
+};
+
+pub const NINE_DLL: &str = "d3d9-nine.dll";
+const D3D9_DLL: &str = "d3d9.dll";
+//prefix folder, nine build folder and the dxvk folder a d3d9.dll could have come from
+const NINE_ARCH_DIRS: [(&str, &str, &str); 2] =
+    [("system32", "lib64", "x64"), ("syswow64", "lib32", "x32")];
+//mesa's d3d9 state tracker, NFS World is 32 bit so only the i386 build counts
+const D3DADAPTER_LOCATIONS: [&str; 5] = [
+    "/usr/lib/i386-linux-gnu/d3d/d3dadapter9.so.1",
+    "/usr/lib32/d3d/d3dadapter9.so.1",
+    "/usr/lib/d3d/d3dadapter9.so.1",
+    "/usr/lib/x86_64-linux-gnu/d3d/d3dadapter9.so.1",
+    "/usr/lib64/d3d/d3dadapter9.so.1",
+];
+const ELF_CLASS_32: u8 = 1;
+
+fn is_32bit_elf(path: &Path) -> bool {
+    let mut header = [0u8; 5];
+    fs::File::open(path)
+        .and_then(|mut file| file.read_exact(&mut header))
+        .is_ok_and(|_| header[..4] == *b"\x7fELF" && header[4] == ELF_CLASS_32)
+}
+
+//some distros keep 32 bit libraries in /usr/lib, so the elf header decides
+pub fn find_d3dadapter() -> Result<PathBuf, CustomError> {
+    D3DADAPTER_LOCATIONS
+        .iter()
+        .map(PathBuf::from)
+        .find(|path| is_32bit_elf(path))
+        .ok_or_else(|| {
+            CustomError::PathError(
+                "Gallium Nine needs Mesa's 32 bit d3dadapter9, install your distro's mesa d3d package"
+                    .to_string(),
+            )
+        })
+}
+
+pub fn setup_nine(wine_prefix: &str, nine_path: &Path) -> Result<(), CustomError> {
+    find_d3dadapter()?;
+
+    let windows_dir = PathBuf::from(wine_prefix).join("drive_c/windows");
+    let installed_dxvk = installed_dxvk_path(wine_prefix);
+
+    for (windows_sub_dir, nine_sub_dir, dxvk_sub_dir) in NINE_ARCH_DIRS {
+        let source_file = nine_path.join(nine_sub_dir).join(NINE_DLL);
+        if !source_file.is_file() {
+            return Err(CustomError::PathError(format!(
+                "Gallium Nine dll not found at {:?}",
+                source_file
+            )));
+        }
+
+        let dll_dir = windows_dir.join(windows_sub_dir);
+
+        //copied like ninewinecfg does, the dll has to live inside the prefix
+        let temp_file_path = dll_dir.join(format!(".{}.tmp", NINE_DLL));
+        if fs::symlink_metadata(&temp_file_path).is_ok() {
+            fs::remove_file(&temp_file_path)?;
+        }
+        fs::copy(&source_file, &temp_file_path)?;
+        fs::rename(&temp_file_path, dll_dir.join(NINE_DLL))?;
+
+        let installed_sources: Vec<PathBuf> = installed_dxvk
+            .iter()
+            .map(|path| path.join(dxvk_sub_dir).join(D3D9_DLL))
+            .collect();
+        backup_builtin_dll(
+            wine_prefix,
+            windows_sub_dir,
+            OsStr::new(D3D9_DLL),
+            &installed_sources,
+        )?;
+
+        let temp_link_path = dll_dir.join(format!(".{}.tmp", D3D9_DLL));
+        if fs::symlink_metadata(&temp_link_path).is_ok() {
+            fs::remove_file(&temp_link_path)?;
+        }
+        unix_fs::symlink(NINE_DLL, &temp_link_path)?;
+        fs::rename(&temp_link_path, dll_dir.join(D3D9_DLL))?;
+    }
+
+    check_nine(Path::new(wine_prefix))?;
+
+    info!("Gallium Nine setup completed ({:?})", nine_path);
+
+    Ok(())
+}
+
+pub fn is_nine_linked(wine_prefix: &str) -> bool {
+    NINE_ARCH_DIRS.iter().any(|(windows_sub_dir, _, _)| {
+        let d3d9 = PathBuf::from(wine_prefix)
+            .join("drive_c/windows")
+            .join(windows_sub_dir)
+            .join(D3D9_DLL);
+        fs::read_link(d3d9).is_ok_and(|target| target == Path::new(NINE_DLL))
+    })
+}
+
+pub fn remove_nine(wine_prefix: &str) -> Result<(), CustomError> {
+    let windows_dir = PathBuf::from(wine_prefix).join("drive_c/windows");
+
+    for (windows_sub_dir, _, _) in NINE_ARCH_DIRS {
+        let dll_dir = windows_dir.join(windows_sub_dir);
+        let d3d9 = dll_dir.join(D3D9_DLL);
+
+        if fs::read_link(&d3d9).is_ok_and(|target| target == Path::new(NINE_DLL)) {
+            //without a backup wine still loads its builtin d3d9
+            restore_builtin_dll(
+                &d3d9,
+                &builtin_backup_path(wine_prefix, windows_sub_dir, OsStr::new(D3D9_DLL)),
+            )?;
+        }
+
+        let nine_dll = dll_dir.join(NINE_DLL);
+        if nine_dll.is_file() {
+            fs::remove_file(nine_dll)?;
+        }
+    }
+
+    info!("Gallium Nine removed from prefix {}", wine_prefix);
+
+    Ok(())
+}
@@ -0,0 +1,288 @@
+use std::path::Path;
+
+use anyhow::anyhow;
+use log::info;
+
+use crate::{
+    types::{
+        config::KeyValue,
+        error::CustomError,
+        prefix::{AudioDriver, MouseWarpOverride, PrefixSettings, WindowsVersion},
+        registry::{RegistryFile, RegistryHive},
+    },
+    utils::registry::{edit_prefix_registry, read_prefix_registry},
+};
+
+//keys below are in user.reg unless noted, the same ones winecfg writes
+const WINE_KEY: &str = r"Software\Wine";
+const EXPLORER_KEY: &str = r"Software\Wine\Explorer";
+const DESKTOPS_KEY: &str = r"Software\Wine\Explorer\Desktops";
+const DRIVERS_KEY: &str = r"Software\Wine\Drivers";
+const DIRECTINPUT_KEY: &str = r"Software\Wine\DirectInput";
+const DLL_OVERRIDES_KEY: &str = r"Software\Wine\DllOverrides";
+//system.reg, what wine reports when no Version override is set
+const CURRENT_VERSION_KEY: &str = r"Software\Microsoft\Windows NT\CurrentVersion";
+
+const DEFAULT_DESKTOP_NAME: &str = "Default";
+const DEFAULT_DESKTOP_SIZE: (u32, u32) = (1024, 768);
+
+//registry name and the lowest build number of each version, newest first
+const WINDOWS_VERSIONS: [(WindowsVersion, &str, u32); 7] = [
+    (WindowsVersion::Win11, "win11", 22000),
+    (WindowsVersion::Win10, "win10", 10240),
+    (WindowsVersion::Win81, "win81", 9600),
+    (WindowsVersion::Win8, "win8", 9200),
+    (WindowsVersion::Win7, "win7", 7600),
+    (WindowsVersion::Vista, "vista", 6000),
+    (WindowsVersion::WinXp, "winxp", 2600),
+];
+
+const AUDIO_DRIVERS: [(AudioDriver, &str); 3] = [
+    (AudioDriver::Pulse, "pulse"),
+    (AudioDriver::Alsa, "alsa"),
+    (AudioDriver::Oss, "oss"),
+];
+
+const MOUSE_WARP_OVERRIDES: [(MouseWarpOverride, &str); 3] = [
+    (MouseWarpOverride::Enable, "enable"),
+    (MouseWarpOverride::Disable, "disable"),
+    (MouseWarpOverride::Force, "force"),
+];
+
+fn registry_name<T: PartialEq>(names: &[(T, &'static str)], value: &T) -> Option<&'static str> {
+    names
+        .iter()
+        .find(|(known, _)| known == value)
+        .map(|(_, name)| *name)
+}
+
+fn from_registry_name<T: Copy>(names: &[(T, &'static str)], name: &str) -> Option<T> {
+    names
+        .iter()
+        .find(|(_, known)| known.eq_ignore_ascii_case(name.trim()))
+        .map(|(value, _)| *value)
+}
+
+fn windows_version_name(version: WindowsVersion) -> &'static str {
+    WINDOWS_VERSIONS
+        .iter()
+        .find(|(known, _, _)| *known == version)
+        .map_or("win10", |(_, name, _)| name)
+}
+
+fn read_windows_version(user_reg: &RegistryHive, system_reg: &RegistryHive) -> WindowsVersion {
+    if let Some(version) = user_reg.get_string(WINE_KEY, "Version").and_then(|name| {
+        WINDOWS_VERSIONS
+            .iter()
+            .find(|(_, known, _)| known.eq_ignore_ascii_case(name))
+    }) {
+        return version.0;
+    }
+
+    system_reg
+        .get_string(CURRENT_VERSION_KEY, "CurrentBuildNumber")
+        .and_then(|build| build.trim().parse::<u32>().ok())
+        .and_then(|build| {
+            WINDOWS_VERSIONS
+                .iter()
+                .find(|(_, _, min_build)| build >= *min_build)
+        })
+        .map_or(WindowsVersion::default(), |(version, _, _)| *version)
+}
+
+fn parse_desktop_size(size: &str) -> Option<(u32, u32)> {
+    let (width, height) = size.trim().split_once('x')?;
+    Some((width.parse().ok()?, height.parse().ok()?))
+}
+
+pub fn read_prefix_settings(wine_prefix: &Path) -> Result<PrefixSettings, CustomError> {
+    let user_reg = read_prefix_registry(wine_prefix, RegistryFile::User)?;
+    let system_reg = read_prefix_registry(wine_prefix, RegistryFile::System)?;
+
+    //the resolution is kept when the desktop is turned off, so show the last one used
+    let desktop_name = user_reg.get_string(EXPLORER_KEY, "Desktop");
+    let (desktop_width, desktop_height) = user_reg
+        .get_string(DESKTOPS_KEY, desktop_name.unwrap_or(DEFAULT_DESKTOP_NAME))
+        .and_then(parse_desktop_size)
+        .unwrap_or(DEFAULT_DESKTOP_SIZE);
+
+    //wine takes a comma separated list and uses the first driver that loads
+    let audio_driver = match user_reg.get_string(DRIVERS_KEY, "Audio") {
+        None => AudioDriver::Auto,
+        Some(drivers) if drivers.trim().is_empty() => AudioDriver::Disabled,
+        Some(drivers) => drivers
+            .split(',')
+            .find_map(|driver| from_registry_name(&AUDIO_DRIVERS, driver))
+            .unwrap_or_default(),
+    };
+
+    let mouse_warp_override = user_reg
+        .get_string(DIRECTINPUT_KEY, "MouseWarpOverride")
+        .and_then(|value| from_registry_name(&MOUSE_WARP_OVERRIDES, value))
+        .unwrap_or_default();
+
+    Ok(PrefixSettings {
+        windows_version: read_windows_version(&user_reg, &system_reg),
+        virtual_desktop: desktop_name.is_some(),
+        desktop_width,
+        desktop_height,
+        audio_driver,
+        mouse_warp_override,
+    })
+}
+
+pub fn write_prefix_settings(
+    wine_prefix: &Path,
+    settings: &PrefixSettings,
+) -> Result<(), CustomError> {
+    if settings.virtual_desktop && (settings.desktop_width == 0 || settings.desktop_height == 0) {
+        return Err(CustomError::Anyhow(anyhow!(
+            "Invalid virtual desktop resolution {}x{}",
+            settings.desktop_width,
+            settings.desktop_height
+        )));
+    }
+
+    edit_prefix_registry(wine_prefix, RegistryFile::User, |user_reg| {
+        user_reg.set_string(
+            WINE_KEY,
+            "Version",
+            windows_version_name(settings.windows_version),
+        );
+
+        if settings.virtual_desktop {
+            let desktop_name = user_reg
+                .get_string(EXPLORER_KEY, "Desktop")
+                .unwrap_or(DEFAULT_DESKTOP_NAME)
+                .to_string();
+            user_reg.set_string(EXPLORER_KEY, "Desktop", &desktop_name);
+            user_reg.set_string(
+                DESKTOPS_KEY,
+                &desktop_name,
+                &format!("{}x{}", settings.desktop_width, settings.desktop_height),
+            );
+        } else {
+            user_reg.delete_value(EXPLORER_KEY, "Desktop");
+        }
+
+        match settings.audio_driver {
+            AudioDriver::Auto => {
+                user_reg.delete_value(DRIVERS_KEY, "Audio");
+            }
+            AudioDriver::Disabled => user_reg.set_string(DRIVERS_KEY, "Audio", ""),
+            driver => {
+                if let Some(name) = registry_name(&AUDIO_DRIVERS, &driver) {
+                    user_reg.set_string(DRIVERS_KEY, "Audio", name);
+                }
+            }
+        }
+
+        //enable is wine's default, no need to keep the value around
+        match registry_name(&MOUSE_WARP_OVERRIDES, &settings.mouse_warp_override) {
+            Some(name) if settings.mouse_warp_override != MouseWarpOverride::Enable => {
+                user_reg.set_string(DIRECTINPUT_KEY, "MouseWarpOverride", name)
+            }
+            _ => {
+                user_reg.delete_value(DIRECTINPUT_KEY, "MouseWarpOverride");
+            }
+        }
+    })?;
+
+    info!("Prefix settings written to {}", wine_prefix.display());
+
+    Ok(())
+}
+
+//WINEDLLOVERRIDES style n,b to the native,builtin winecfg writes, d or nothing disables the dll
+fn registry_load_order(value: &str) -> String {
+    value
+        .split(',')
+        .filter_map(|mode| match mode.trim() {
+            "n" | "native" => Some("native"),
+            "b" | "builtin" => Some("builtin"),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+//one entry may name several dlls, later entries win like they do in WINEDLLOVERRIDES
+fn registry_dll_overrides(overrides: &[KeyValue]) -> Vec<(String, String)> {
+    let mut entries: Vec<(String, String)> = Vec::new();
+
+    for dll_override in overrides {
+        let load_order = registry_load_order(&dll_override.value);
+        for dll in dll_override
+            .key
+            .split(',')
+            .map(str::trim)
+            .filter(|dll| !dll.is_empty())
+        {
+            entries.retain(|(name, _)| !name.eq_ignore_ascii_case(dll));
+            entries.push((dll.to_string(), load_order.clone()));
+        }
+    }
+
+    entries
+}
+
+//dlls the utility wrote before that are no longer configured, winecfg's own entries are left alone
+fn stale_dll_overrides(
+    entries: &[(String, String)],
+    previous_overrides: &[KeyValue],
+) -> Vec<String> {
+    registry_dll_overrides(previous_overrides)
+        .into_iter()
+        .map(|(dll, _)| dll)
+        .filter(|dll| {
+            !entries
+                .iter()
+                .any(|(name, _)| name.eq_ignore_ascii_case(dll))
+        })
+        .collect()
+}
+
+//true when sync_dll_overrides would change user.reg
+pub fn dll_overrides_changed(
+    wine_prefix: &Path,
+    overrides: &[KeyValue],
+    previous_overrides: &[KeyValue],
+) -> Result<bool, CustomError> {
+    let user_reg = read_prefix_registry(wine_prefix, RegistryFile::User)?;
+    let entries = registry_dll_overrides(overrides);
+
+    let has_stale = stale_dll_overrides(&entries, previous_overrides)
+        .iter()
+        .any(|dll| user_reg.get_value(DLL_OVERRIDES_KEY, dll).is_some());
+    let has_drift = entries.iter().any(|(dll, load_order)| {
+        user_reg.get_string(DLL_OVERRIDES_KEY, dll) != Some(load_order.as_str())
+    });
+
+    Ok(has_stale || has_drift)
+}
+
+pub fn sync_dll_overrides(
+    wine_prefix: &Path,
+    overrides: &[KeyValue],
+    previous_overrides: &[KeyValue],
+) -> Result<(), CustomError> {
+    let entries = registry_dll_overrides(overrides);
+    let stale = stale_dll_overrides(&entries, previous_overrides);
+
+    edit_prefix_registry(wine_prefix, RegistryFile::User, |user_reg| {
+        for dll in &stale {
+            user_reg.delete_value(DLL_OVERRIDES_KEY, dll);
+        }
+        for (dll, load_order) in &entries {
+            user_reg.set_string(DLL_OVERRIDES_KEY, dll, load_order);
+        }
+    })?;
+
+    info!(
+        "Dll overrides synced to the prefix registry ({} set, {} removed)",
+        entries.len(),
+        stale.len()
+    );
+
+    Ok(())
+}
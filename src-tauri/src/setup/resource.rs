@@ -3,35 +3,116 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use log::info;
+use log::{info, warn};
+use tauri::{path::BaseDirectory, Manager, Window};
 
 use crate::{
-    types::error::CustomError,
+    types::{archive::ExtractOptions, config::NetworkComponent, error::CustomError},
     utils::{
-        downloader::{build_client, download_using_url, get_latest_download_url},
-        fs::extract_archive,
+        archive::extract_archive,
+        cache::find_newest_cached_file,
+        cancel::CancellationToken,
+        checksum::verify_sha256,
+        downloader::{
+            build_client, download_using_url, fetch_published_checksum, get_latest_asset,
+        },
+        emitter::report_extract_progress,
     },
 };
 
-pub fn download_game_launcher(dest: &Path) -> Result<PathBuf, CustomError> {
+include!(concat!(env!("OUT_DIR"), "/bundled_checksums.rs"));
+
+const LAUNCHER_DOWNLOADS_URL: &str =
+    "https://github.com/SoapboxRaceWorld/GameLauncher_NFSW/releases/download/";
+const LAUNCHER_ASSET_PREFIX: &str = "Unix.Release";
+
+//file names the bundled resources are written under in the tools folder
+pub const BUNDLED_RESOURCE_FILES: [&str; 6] = [
+    "wine-10.8-staging-amd64.tar.xz",
+    "dxvk-1.10.3.tar.gz",
+    "wine-mono-10.0.0-x86.msi",
+    "vc_redist.x86.exe",
+    "vc_redist.x64.exe",
+    "overrides.reg",
+];
+
+pub fn verify_bundled_resource(tools_dir: &Path, file_name: &str) -> Result<(), CustomError> {
+    //the tools folder lowercases the VC++ redistributable names, so the
+    //manifest generated from the resources folder is matched case-insensitively
+    let (_, expected) = BUNDLED_CHECKSUMS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(file_name))
+        .ok_or_else(|| {
+            CustomError::PathError(format!("No bundled checksum found for {}", file_name))
+        })?;
+
+    verify_sha256(&tools_dir.join(file_name), expected)
+}
+
+pub fn download_game_launcher(
+    dest: &Path,
+    network: &NetworkComponent,
+    window: &Window,
+    token: Option<&CancellationToken>,
+) -> Result<PathBuf, CustomError> {
     fs::create_dir_all(dest)?;
 
-    let client = build_client()?;
+    let cache_dir = window
+        .path()
+        .resolve("sbrw-utility/cache", BaseDirectory::Data)?;
+    let client = build_client(network, &cache_dir)?;
 
     let game_launcher_repo_url =
-        "https://api.github.com/repos/SoapboxRaceWorld/GameLauncher_NFSW/releases/latest";
-    let game_launcher_url =
-        get_latest_download_url(&client, game_launcher_repo_url, "Unix.Release")?;
+        client.api_url("repos/SoapboxRaceWorld/GameLauncher_NFSW/releases/latest");
+    let asset = match get_latest_asset(&client, &game_launcher_repo_url, LAUNCHER_ASSET_PREFIX) {
+        Ok(asset) => asset,
+        Err(err) => {
+            //offline the newest launcher a previous setup or repair cached still installs
+            let cached = find_newest_cached_file(&cache_dir, |entry| {
+                entry.url.starts_with(LAUNCHER_DOWNLOADS_URL)
+                    && entry.file_name.starts_with(LAUNCHER_ASSET_PREFIX)
+            })?;
+            let Some((entry, cached_path)) = cached else {
+                return Err(err);
+            };
+
+            warn!(
+                "Latest launcher release unavailable ({}), using cached {}",
+                err, entry.file_name
+            );
+            let launcher_path = dest.join(&entry.file_name);
+            fs::copy(&cached_path, &launcher_path)?;
+            verify_sha256(&launcher_path, &entry.sha256)?;
+
+            return Ok(launcher_path);
+        }
+    };
+    let game_launcher_url = &asset.browser_download_url;
 
     // Extract file name from URL
-    let file_name = Path::new(&game_launcher_url)
+    let file_name = Path::new(game_launcher_url)
         .file_name()
         .and_then(|os_str| os_str.to_str())
         .ok_or_else(|| CustomError::PathError("Invalid file name in URL".to_string()))?;
 
+    //the launcher gets executed, so it's never used unverified
+    let checksum = match asset.sha256() {
+        Some(checksum) => checksum,
+        None => fetch_published_checksum(&client, game_launcher_url, file_name)?
+            .ok_or_else(|| CustomError::MissingChecksum(file_name.to_string()))?,
+    };
+
     //downloading the game launcher
     info!("Downloading SBRW launcher");
-    let launcher_path = download_using_url(&client, &game_launcher_url, dest, file_name)?;
+    let launcher_path = download_using_url(
+        &client,
+        game_launcher_url,
+        dest,
+        file_name,
+        Some(&checksum),
+        window,
+        token,
+    )?;
 
     Ok(launcher_path)
 }
@@ -55,6 +136,10 @@ pub fn write_bundled_resources(tools_dir: &Path) -> Result<(), CustomError> {
     fs::write(tools_dir.join("vc_redist.x64.exe"), vc_redist_x64_bytes)?;
     fs::write(tools_dir.join("overrides.reg"), overrides_reg_bytes)?;
 
+    for file_name in BUNDLED_RESOURCE_FILES {
+        verify_bundled_resource(tools_dir, file_name)?;
+    }
+
     Ok(())
 }
 
@@ -62,6 +147,7 @@ pub fn extract_bundled_archives(
     tools_dir: &Path,
     runners_wine_dir: &Path,
     runtime_dxvk_dir: &Path,
+    window: &Window,
 ) -> Result<(), CustomError> {
     let wine_dir_path = runners_wine_dir.join("wine-10.8-staging-amd64");
     let dxvk_dir_path = runtime_dxvk_dir.join("dxvk-1.10.3");
@@ -75,10 +161,22 @@ pub fn extract_bundled_archives(
     //create dxvk version directory
     fs::create_dir_all(&dxvk_dir_path)?;
 
+    //the tools folder may come from an earlier run, so check it before extracting
+    verify_bundled_resource(tools_dir, "wine-10.8-staging-amd64.tar.xz")?;
+    verify_bundled_resource(tools_dir, "dxvk-1.10.3.tar.gz")?;
+
     //extracting bundled archives
     info!("Extracting bundled tarballs");
-    extract_archive(&wine_tar_path, &wine_dir_path)?;
-    extract_archive(&dxvk_tar_path, &dxvk_dir_path)?;
+    //both tarballs wrap their contents in a top level version folder
+    let options = ExtractOptions {
+        strip_components: 1,
+        ..Default::default()
+    };
+    let mut on_progress = |progress| {
+        report_extract_progress(window, progress).ok();
+    };
+    extract_archive(&wine_tar_path, &wine_dir_path, &options, &mut on_progress)?;
+    extract_archive(&dxvk_tar_path, &dxvk_dir_path, &options, &mut on_progress)?;
 
     Ok(())
 }
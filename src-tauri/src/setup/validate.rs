@@ -1,13 +1,29 @@
 use std::{
     fs,
-    io::{BufRead, BufReader},
     path::{Path, PathBuf},
 };
 
 use anyhow::anyhow;
 use log::info;
 
-use crate::types::error::CustomError;
+use crate::{
+    setup::resource::{verify_bundled_resource, BUNDLED_RESOURCE_FILES},
+    setup::{nine::NINE_DLL, prefix::dll_overrides_changed},
+    types::{
+        config::{D3D9Backend, DxvkInstallMode, KeyValue},
+        error::CustomError,
+        registry::RegistryFile,
+    },
+    utils::{
+        checksum::sha256_file,
+        initialize::{get_d3d9_settings, get_dxvk_install_mode},
+        registry::{read_prefix_registry, read_regedit_file},
+    },
+};
+
+const VC_RUNTIME_X64_KEY: &str = r"Software\Microsoft\VisualStudio\14.0\VC\Runtimes\x64";
+const VC_RUNTIME_X86_KEY: &str =
+    r"Software\Wow6432Node\Microsoft\VisualStudio\14.0\VC\Runtimes\x86";
 
 pub fn check_wine_prefix(wine_prefix_path: &Path) -> Result<(), CustomError> {
     let user_reg = wine_prefix_path.join("user.reg");
@@ -53,6 +69,29 @@ pub fn check_wine_bin_dir(wine_path: &Path) -> Result<(), CustomError> {
     Ok(())
 }
 
+pub fn check_bundled_resources(tools_dir: &Path) -> Result<(), CustomError> {
+    let missing: Vec<&str> = BUNDLED_RESOURCE_FILES
+        .iter()
+        .filter(|file_name| !tools_dir.join(file_name).is_file())
+        .copied()
+        .collect();
+
+    if !missing.is_empty() {
+        return Err(CustomError::PathError(format!(
+            "Missing bundled resources in tools folder: {:?}",
+            missing
+        )));
+    }
+
+    for file_name in BUNDLED_RESOURCE_FILES {
+        verify_bundled_resource(tools_dir, file_name)?;
+    }
+
+    info!("Validation: bundled resources found ({:?})", tools_dir);
+
+    Ok(())
+}
+
 fn collect_dlls(path: &Path) -> Result<Vec<String>, CustomError> {
     Ok(fs::read_dir(path)?
         .filter_map(|entry| {
@@ -76,11 +115,21 @@ fn check_dll_symlinks(
     dll_dir: &Path,
     expected_path: &Path,
     arch_label: &str,
+    install_mode: DxvkInstallMode,
     missing: &mut Vec<String>,
 ) -> Result<(), CustomError> {
     for dll in dlls {
         let dll_path = dll_dir.join(dll);
-        if dll_path.is_symlink() {
+        if install_mode == DxvkInstallMode::Copy {
+            if dll_path.is_symlink() || !dll_path.is_file() {
+                missing.push(format!("{} DLL copy missing: {:?}", arch_label, dll_path));
+            } else if sha256_file(&dll_path)? != sha256_file(&expected_path.join(dll))? {
+                missing.push(format!(
+                    "{} DLL copy differs from {:?}: {:?}",
+                    arch_label, expected_path, dll_path
+                ));
+            }
+        } else if dll_path.is_symlink() {
             let target = fs::read_link(&dll_path)?;
             if !target.starts_with(expected_path) {
                 missing.push(format!(
@@ -101,7 +150,12 @@ fn check_dll_symlinks(
     Ok(())
 }
 
-pub fn check_dxvk_symlinks(dxvk_path: &Path, wine_prefix_path: &Path) -> Result<(), CustomError> {
+pub fn check_dxvk_symlinks(
+    dxvk_path: &Path,
+    wine_prefix_path: &Path,
+    install_mode: DxvkInstallMode,
+    d3d9_backend: D3D9Backend,
+) -> Result<(), CustomError> {
     if !dxvk_path.exists() {
         return Err(CustomError::PathError(format!(
             "provided dxvk path not found"
@@ -114,8 +168,12 @@ pub fn check_dxvk_symlinks(dxvk_path: &Path, wine_prefix_path: &Path) -> Result<
     let system32_path = wine_prefix_path.join("drive_c/windows/system32");
     let syswow64_path = wine_prefix_path.join("drive_c/windows/syswow64");
 
-    let dxvk_x64_dlls: Vec<String> = collect_dlls(&dxvk_x64_path)?;
-    let dxvk_x32_dlls: Vec<String> = collect_dlls(&dxvk_x32_path)?;
+    //dlls another d3d9 backend owns are not dxvk's to validate
+    let excluded = d3d9_backend.dxvk_excluded_dlls();
+    let mut dxvk_x64_dlls: Vec<String> = collect_dlls(&dxvk_x64_path)?;
+    let mut dxvk_x32_dlls: Vec<String> = collect_dlls(&dxvk_x32_path)?;
+    dxvk_x64_dlls.retain(|dll| !excluded.contains(&dll.as_str()));
+    dxvk_x32_dlls.retain(|dll| !excluded.contains(&dll.as_str()));
 
     let mut missing = Vec::new();
 
@@ -125,6 +183,7 @@ pub fn check_dxvk_symlinks(dxvk_path: &Path, wine_prefix_path: &Path) -> Result<
         &system32_path,
         &dxvk_x64_path,
         "System32",
+        install_mode,
         &mut missing,
     )?;
 
@@ -134,6 +193,7 @@ pub fn check_dxvk_symlinks(dxvk_path: &Path, wine_prefix_path: &Path) -> Result<
         &syswow64_path,
         &dxvk_x32_path,
         "Syswow64",
+        install_mode,
         &mut missing,
     )?;
 
@@ -145,44 +205,45 @@ pub fn check_dxvk_symlinks(dxvk_path: &Path, wine_prefix_path: &Path) -> Result<
     }
 
     info!(
-        "Validation: DXVK symlinks are properly set ({:?})",
-        dxvk_path
+        "Validation: DXVK dlls are properly set ({:?}, {:?})",
+        dxvk_path, install_mode
     );
 
     Ok(())
 }
 
-pub fn check_vc_runtimes(wine_prefix_path: &Path) -> Result<(), CustomError> {
-    let system_reg_file = wine_prefix_path.join("system.reg");
-    let file = fs::File::open(system_reg_file)?;
-    let reader = BufReader::new(file);
-    let mut section = String::new();
-
-    let mut x64_installed = false;
-    let mut x86_installed = false;
-
-    for line in reader.lines() {
-        let line = line?;
-
-        if line.starts_with('[') {
-            section = line.trim().to_string();
-            continue;
+pub fn check_nine(wine_prefix_path: &Path) -> Result<(), CustomError> {
+    for windows_sub_dir in ["system32", "syswow64"] {
+        let dll_dir = wine_prefix_path
+            .join("drive_c/windows")
+            .join(windows_sub_dir);
+
+        if !dll_dir.join(NINE_DLL).is_file() {
+            return Err(CustomError::PathError(format!(
+                "Gallium Nine dll missing in {:?}",
+                dll_dir
+            )));
         }
 
-        if line.contains("\"Installed\"=dword:00000001") {
-            if section.starts_with(r"[Software\\Microsoft\\VisualStudio\\14.0\\VC\\Runtimes\\x64]")
-            {
-                x64_installed = true;
-            }
-            if section.starts_with(
-                r"[Software\\Wow6432Node\\Microsoft\\VisualStudio\\14.0\\VC\\Runtimes\\x86]",
-            ) {
-                x86_installed = true;
-            }
+        let d3d9_target = fs::read_link(dll_dir.join("d3d9.dll")).ok();
+        if d3d9_target.as_deref() != Some(Path::new(NINE_DLL)) {
+            return Err(CustomError::PathError(format!(
+                "d3d9.dll in {:?} does not point to {}",
+                dll_dir, NINE_DLL
+            )));
         }
     }
 
-    if !x64_installed || !x86_installed {
+    info!("Validation: Gallium Nine is set up");
+
+    Ok(())
+}
+
+pub fn check_vc_runtimes(wine_prefix_path: &Path) -> Result<(), CustomError> {
+    let system_reg = read_prefix_registry(wine_prefix_path, RegistryFile::System)?;
+    let is_installed = |key| system_reg.get_dword(key, "Installed") == Some(1);
+
+    if !is_installed(VC_RUNTIME_X64_KEY) || !is_installed(VC_RUNTIME_X86_KEY) {
         return Err(CustomError::Anyhow(anyhow!(
             "VC++ runtimes are not installed correctly"
         )));
@@ -193,10 +254,76 @@ pub fn check_vc_runtimes(wine_prefix_path: &Path) -> Result<(), CustomError> {
     Ok(())
 }
 
+//wine-mono's msi installs here, wine looks for it in the same place
+pub fn check_mono_runtime(wine_prefix_path: &Path) -> Result<(), CustomError> {
+    let mono_path = wine_prefix_path.join("drive_c/windows/mono/mono-2.0");
+    if !mono_path.is_dir() {
+        return Err(CustomError::PathError(format!(
+            "Wine mono not found in wine prefix {:?}",
+            wine_prefix_path
+        )));
+    }
+
+    info!("Validation: wine mono found ({:?})", mono_path);
+
+    Ok(())
+}
+
+//every value the regedit file sets has to be in the prefix registry
+pub fn check_regedit_import(
+    wine_prefix_path: &Path,
+    reg_file_path: &Path,
+) -> Result<(), CustomError> {
+    let user_reg = read_prefix_registry(wine_prefix_path, RegistryFile::User)?;
+    let system_reg = read_prefix_registry(wine_prefix_path, RegistryFile::System)?;
+
+    let missing: Vec<String> = read_regedit_file(reg_file_path)?
+        .into_iter()
+        .filter(|(file, key_path, name, value)| {
+            let hive = match file {
+                RegistryFile::User => &user_reg,
+                RegistryFile::System => &system_reg,
+            };
+            hive.get_value(key_path, name) != Some(value)
+        })
+        .map(|(_, key_path, name, _)| format!("{}\\{}", key_path, name))
+        .collect();
+
+    if !missing.is_empty() {
+        return Err(CustomError::Registry(format!(
+            "Values from {:?} missing in the prefix registry: {:?}",
+            reg_file_path, missing
+        )));
+    }
+
+    info!(
+        "Validation: registry values from {:?} are set",
+        reg_file_path
+    );
+
+    Ok(())
+}
+
+pub fn check_dll_overrides(
+    wine_prefix_path: &Path,
+    overrides: &[KeyValue],
+) -> Result<(), CustomError> {
+    if dll_overrides_changed(wine_prefix_path, overrides, &[])? {
+        return Err(CustomError::Anyhow(anyhow!(
+            "Dll overrides in the prefix registry differ from the settings"
+        )));
+    }
+
+    info!("Validation: dll overrides match the prefix registry");
+
+    Ok(())
+}
+
 pub fn validate_installation(
     wine_prefix: &str,
     wine_path: &Path,
     dxvk_path: &Path,
+    dxvk_enabled: bool,
 ) -> Result<(), CustomError> {
     //check wine prefix' registry files existence
     let wine_prefix_path = PathBuf::from(wine_prefix);
@@ -208,8 +335,15 @@ pub fn validate_installation(
     //check wine bin existence
     check_wine_bin_dir(&wine_path)?;
 
-    //check dxvk symlinks
-    check_dxvk_symlinks(&dxvk_path, &wine_prefix_path)?;
+    //check dxvk symlinks, a prefix with dxvk removed keeps wine's own dlls
+    if dxvk_enabled {
+        check_dxvk_symlinks(
+            &dxvk_path,
+            &wine_prefix_path,
+            get_dxvk_install_mode(),
+            get_d3d9_settings().backend,
+        )?;
+    }
 
     //check runtimes
     check_vc_runtimes(&wine_prefix_path)?;
@@ -1,15 +1,25 @@
 use log::{info, warn};
 use std::{
     path::{Path, PathBuf},
+    process::Command,
     thread, time,
 };
 
-use crate::{setup::check_vc_runtimes, types::error::CustomError, utils::process::run_command};
+use crate::{
+    setup::check_vc_runtimes,
+    types::error::CustomError,
+    utils::{
+        cancel::CancellationToken,
+        process::{is_path_in_use, run_command},
+        runner::resolve_wineserver,
+    },
+};
 
 fn install_mono_runtime(
     wine_path: &Path,
     wine_prefix: &str,
     mono_msi_path: &Path,
+    token: &CancellationToken,
 ) -> Result<(), CustomError> {
     let mono_msi_str = mono_msi_path
         .to_str()
@@ -22,6 +32,7 @@ fn install_mono_runtime(
         Some(&["msiexec", "/i", mono_msi_str]),
         Some(&[("WINEPREFIX", wine_prefix)]),
         None,
+        Some(token),
     )?;
 
     Ok(())
@@ -32,6 +43,7 @@ fn install_vc_runtimes(
     wine_prefix: &str,
     vc_redist_x86: &Path,
     vc_redist_x64: &Path,
+    token: &CancellationToken,
 ) -> Result<(), CustomError> {
     let vc_redist_x86_str = vc_redist_x86
         .to_str()
@@ -48,20 +60,25 @@ fn install_vc_runtimes(
         Some(&[vc_redist_x86_str, "/quiet", "/norestart"]),
         Some(&[("WINEPREFIX", wine_prefix)]),
         None,
+        Some(token),
     )?;
 
+    token.check()?;
+
     //set up x64 vc redist
     run_command(
         wine_path,
         Some(&[vc_redist_x64_str, "/quiet", "/norestart"]),
         Some(&[("WINEPREFIX", wine_prefix)]),
         None,
+        Some(token),
     )?;
 
     let wine_prefix_path = PathBuf::from(wine_prefix);
 
     //waiting until installation reflects in registry files
     for attempt in 0..60 {
+        token.check()?;
         thread::sleep(time::Duration::from_secs(1));
 
         if check_vc_runtimes(&wine_prefix_path).is_ok() {
@@ -81,6 +98,7 @@ fn set_regedit_overrides(
     wine_path: &Path,
     wine_prefix: &str,
     overrides_reg_path: &Path,
+    token: &CancellationToken,
 ) -> Result<(), CustomError> {
     let overrides_reg_str = overrides_reg_path
         .to_str()
@@ -91,6 +109,7 @@ fn set_regedit_overrides(
         Some(&["regedit", "/s", overrides_reg_str]),
         Some(&[("WINEPREFIX", wine_prefix)]),
         None,
+        Some(token),
     )?;
 
     info!("Dll overrides set");
@@ -103,9 +122,12 @@ pub fn install_runtimes(
     wine_path: &Path,
     wine_prefix: &str,
     tools_dir: &Path,
+    token: &CancellationToken,
 ) -> Result<(), CustomError> {
     //execute wine mono msi
-    install_mono_runtime(wine_path, wine_prefix, mono_msi_path)?;
+    install_mono_runtime(wine_path, wine_prefix, mono_msi_path, token)?;
+
+    token.check()?;
 
     //run vcruntime with winetricks
     install_vc_runtimes(
@@ -113,10 +135,18 @@ pub fn install_runtimes(
         wine_prefix,
         &tools_dir.join("vc_redist.x86.exe"),
         &tools_dir.join("vc_redist.x64.exe"),
+        token,
     )?;
 
+    token.check()?;
+
     //setting registry overrides
-    set_regedit_overrides(wine_path, wine_prefix, &tools_dir.join("overrides.reg"))?;
+    set_regedit_overrides(
+        wine_path,
+        wine_prefix,
+        &tools_dir.join("overrides.reg"),
+        token,
+    )?;
 
     Ok(())
 }
@@ -124,6 +154,7 @@ pub fn install_runtimes(
 pub fn initialize_wine_environment(
     wineboot_path: &Path,
     wine_prefix: &str,
+    token: &CancellationToken,
 ) -> Result<(), CustomError> {
     //run wineboot command to initialize isolated wine environment
     info!("Running wineboot on wine prefix");
@@ -136,6 +167,7 @@ pub fn initialize_wine_environment(
             ("WINEDLLOVERRIDES", "winemenubuilder="),
         ]),
         None,
+        Some(token),
     )?;
 
     let wine_prefix_path = Path::new(wine_prefix);
@@ -146,6 +178,7 @@ pub fn initialize_wine_environment(
 
     //waiting until registry files are created
     for attempt in 0..120 {
+        token.check()?;
         thread::sleep(time::Duration::from_millis(500));
         if user_reg.exists() && system_reg.exists() && userdef_reg.exists() {
             info!("Wine registry files created after {}s.", attempt + 1);
@@ -161,3 +194,36 @@ pub fn initialize_wine_environment(
         "Timed out. wine initialization failed, Try rerunning the setup".to_string(),
     ))
 }
+
+pub fn stop_wine_prefix(wineserver_path: &Path, wine_prefix: &str) -> Result<(), CustomError> {
+    if !wineserver_path.exists() {
+        return Ok(());
+    }
+
+    //killing every wine process of the prefix and waiting for wineserver to go down,
+    //so nothing keeps writing into the prefix after a cancel
+    info!("Stopping wine processes in prefix {}", wine_prefix);
+    Command::new(wineserver_path)
+        .arg("-k")
+        .env("WINEPREFIX", wine_prefix)
+        .status()?;
+
+    Command::new(wineserver_path)
+        .arg("-w")
+        .env("WINEPREFIX", wine_prefix)
+        .status()?;
+
+    Ok(())
+}
+
+//wineserver keeps the registry in memory and writes it back when it exits, so it has to go first
+pub fn release_prefix(wine_prefix: &str, wine_path: &Path) -> Result<(), CustomError> {
+    if is_path_in_use(Path::new(wine_prefix)) {
+        return Err(CustomError::InUse {
+            name: "The wine prefix".to_string(),
+            user: "a running game".to_string(),
+        });
+    }
+
+    stop_wine_prefix(&resolve_wineserver(wine_path), wine_prefix)
+}
@@ -0,0 +1,20 @@
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Default)]
+pub struct ExtractOptions {
+    //number of leading path components to drop, same as tar --strip-components
+    pub strip_components: usize,
+    //only entries below this archive path are extracted, with the path itself stripped
+    pub root: Option<PathBuf>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ExtractProgress {
+    pub file_name: String,
+    pub entry: String,
+    pub entries_extracted: u64,
+    pub processed_bytes: u64,
+    pub total_bytes: u64,
+}
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CacheEntry {
+    pub url: String,
+    pub file_name: String,
+    pub sha256: String,
+    pub size: u64,
+    pub last_used: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CacheIndex {
+    pub entries: Vec<CacheEntry>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct CacheInfo {
+    pub path: String,
+    pub total_size: u64,
+    pub max_size: u64,
+    pub entries: Vec<CacheEntry>,
+}
@@ -1,15 +1,26 @@
 use serde::{Deserialize, Serialize};
 
+use crate::types::runner::{DxvkSource, RunnerKind, RunnerOrigin, RunnerSource};
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RunnerVersion {
     pub name: String,
     pub path: String,
+
+    #[serde(default)]
+    pub version: Option<String>,
+
+    #[serde(default)]
+    pub origin: RunnerOrigin,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RuntimeVersion {
     pub name: String,
     pub path: String,
+
+    #[serde(default)]
+    pub origin: RunnerOrigin,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -18,6 +29,21 @@ pub struct Config {
     pub dxvk: DXVKComponent,
     pub game: GameComponent,
 
+    #[serde(default)]
+    pub cache: CacheComponent,
+
+    #[serde(default)]
+    pub network: NetworkComponent,
+
+    #[serde(default)]
+    pub runners: RunnersComponent,
+
+    #[serde(default)]
+    pub search_paths: SearchPathsComponent,
+
+    #[serde(default)]
+    pub d3d9: D3D9Component,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub available_wine_versions: Option<Vec<RunnerVersion>>,
 
@@ -39,6 +65,47 @@ pub struct DXVKComponent {
     pub version: String,
     pub path: String,
     pub enabled: bool,
+
+    #[serde(default)]
+    pub install_mode: DxvkInstallMode,
+}
+
+//what serves direct3d 9, dxvk keeps d3d10/11 either way
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum D3D9Backend {
+    Wined3d,
+    #[default]
+    Dxvk,
+    Nine,
+}
+
+impl D3D9Backend {
+    //dlls dxvk leaves to the selected backend
+    pub fn dxvk_excluded_dlls(&self) -> &'static [&'static str] {
+        match self {
+            D3D9Backend::Dxvk => &[],
+            D3D9Backend::Wined3d | D3D9Backend::Nine => &["d3d9.dll"],
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct D3D9Component {
+    pub backend: D3D9Backend,
+    //gallium nine standalone build, downloaded when nine is picked
+    pub nine_version: String,
+    pub nine_path: String,
+}
+
+//copies survive moving or backing up the prefix, links don't
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DxvkInstallMode {
+    #[default]
+    Symlink,
+    Copy,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -49,6 +116,54 @@ pub struct GameComponent {
     pub mangohud: bool,
     pub environment_variables: Vec<KeyValue>,
     pub dll_overrides: Vec<KeyValue>,
+
+    //also write dll_overrides into the prefix registry, not just WINEDLLOVERRIDES at launch
+    #[serde(default)]
+    pub persist_dll_overrides: bool,
+}
+
+impl GameComponent {
+    pub fn persisted_dll_overrides(&self) -> &[KeyValue] {
+        if self.persist_dll_overrides {
+            &self.dll_overrides
+        } else {
+            &[]
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CacheComponent {
+    pub max_size_mb: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct NetworkComponent {
+    pub api_base_url: String,
+    pub mirror_urls: Vec<String>,
+    pub proxy: Option<String>,
+    pub github_token: Option<String>,
+    pub connect_timeout_secs: u64,
+    pub read_timeout_secs: u64,
+    pub max_retries: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct RunnersComponent {
+    pub sources: Vec<RunnerSource>,
+    pub dxvk_sources: Vec<DxvkSource>,
+    pub releases_per_source: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct SearchPathsComponent {
+    //look into Lutris, Steam, Bottles and Heroic folders as well
+    pub auto_detect: bool,
+    pub runner_paths: Vec<String>,
+    pub dxvk_paths: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -63,8 +178,93 @@ impl Config {
             wine,
             dxvk,
             game,
+            cache: CacheComponent::default(),
+            network: NetworkComponent::default(),
+            runners: RunnersComponent::default(),
+            search_paths: SearchPathsComponent::default(),
+            d3d9: D3D9Component::default(),
             available_wine_versions: None,
             available_dxvk_versions: None,
         }
     }
 }
+
+impl Default for CacheComponent {
+    fn default() -> Self {
+        Self { max_size_mb: 4096 }
+    }
+}
+
+impl Default for NetworkComponent {
+    fn default() -> Self {
+        Self {
+            api_base_url: "https://api.github.com".to_string(),
+            mirror_urls: Vec::new(),
+            proxy: None,
+            github_token: None,
+            connect_timeout_secs: 15,
+            read_timeout_secs: 60,
+            max_retries: 3,
+        }
+    }
+}
+
+impl Default for RunnersComponent {
+    fn default() -> Self {
+        let source = |name: &str, repo: &str, kind: RunnerKind, asset_suffix: &str| RunnerSource {
+            name: name.to_string(),
+            repo: repo.to_string(),
+            kind,
+            asset_suffix: asset_suffix.to_string(),
+        };
+        let dxvk_source = |name: &str, repo: &str, asset_suffix: &str| DxvkSource {
+            name: name.to_string(),
+            repo: repo.to_string(),
+            asset_suffix: asset_suffix.to_string(),
+        };
+
+        Self {
+            sources: vec![
+                source(
+                    "Wine-GE",
+                    "GloriousEggroll/wine-ge-custom",
+                    RunnerKind::Wine,
+                    ".tar.xz",
+                ),
+                source(
+                    "GE-Proton",
+                    "GloriousEggroll/proton-ge-custom",
+                    RunnerKind::Proton,
+                    ".tar.gz",
+                ),
+                source(
+                    "Kron4ek",
+                    "Kron4ek/Wine-Builds",
+                    RunnerKind::Wine,
+                    "-amd64.tar.xz",
+                ),
+                source(
+                    "wine-tkg",
+                    "Frogging-Family/wine-tkg-git",
+                    RunnerKind::Wine,
+                    ".tar.zst",
+                ),
+            ],
+            dxvk_sources: vec![
+                dxvk_source("DXVK", "doitsujin/dxvk", ".tar.gz"),
+                dxvk_source("DXVK-async", "Sporif/dxvk-async", ".tar.gz"),
+            ],
+            releases_per_source: 10,
+        }
+    }
+}
+
+impl Default for SearchPathsComponent {
+    fn default() -> Self {
+        Self {
+            auto_detect: true,
+            runner_paths: Vec::new(),
+            dxvk_paths: Vec::new(),
+        }
+    }
+}
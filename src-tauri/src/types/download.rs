@@ -0,0 +1,10 @@
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct DownloadProgress {
+    pub file_name: String,
+    pub downloaded_bytes: u64,
+    pub total_bytes: Option<u64>,
+    pub bytes_per_second: u64,
+    pub eta_seconds: Option<u64>,
+}
@@ -24,8 +24,39 @@ pub enum CustomError {
     #[error("Json error: {0}")]
     Json(#[from] serde_json::Error),
 
+    #[error("Zip error: {0}")]
+    Zip(#[from] zip::result::ZipError),
+
     #[error("Invalid path: {0}")]
     PathError(String),
+
+    #[error("Unsafe archive entry: {0}")]
+    UnsafeArchiveEntry(String),
+
+    #[error("Registry error: {0}")]
+    Registry(String),
+
+    #[error("Unsupported archive format: {0}")]
+    UnsupportedArchive(String),
+
+    #[error("{name} is in use by {user}")]
+    InUse { name: String, user: String },
+
+    #[error("Operation cancelled")]
+    Cancelled,
+
+    #[error("GitHub API rate limit reached, it resets in {reset_in_minutes} minutes. Add a GitHub token in the network settings to raise the limit")]
+    RateLimited { reset_in_minutes: u64 },
+
+    #[error("{file} is corrupt: expected sha256 {expected}, got {actual}")]
+    ChecksumMismatch {
+        file: String,
+        expected: String,
+        actual: String,
+    },
+
+    #[error("No checksum is published for {0}, it can't be verified")]
+    MissingChecksum(String),
 }
 
 impl serde::Serialize for CustomError {
@@ -43,5 +74,7 @@ pub enum ValidationError {
     MissingGameLauncher,
     // MissingWineBinDir,
     MissingDXVKSymlinks,
+    MissingGalliumNine,
+    DllOverridesDrift,
     // MissingVCRuntimes,
 }
@@ -1,4 +1,12 @@
+pub mod archive;
+pub mod cache;
 pub mod config;
+pub mod download;
 pub mod error;
+pub mod prefix;
+pub mod registry;
+pub mod runner;
 pub mod setup;
+pub mod snapshot;
+pub mod winetricks;
 pub mod wizard;
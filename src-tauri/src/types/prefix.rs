@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+
+use crate::types::config::DxvkInstallMode;
+
+//what the prefix really has linked, config.dxvk only says what the user picked
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct PrefixDxvkState {
+    pub installed: bool,
+    pub version: Option<String>,
+    pub path: Option<String>,
+
+    #[serde(default)]
+    pub install_mode: DxvkInstallMode,
+    pub updated_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum WindowsVersion {
+    Win11,
+    #[default]
+    Win10,
+    Win81,
+    Win8,
+    Win7,
+    Vista,
+    WinXp,
+}
+
+//empty Audio value turns sound off, a missing one lets wine pick
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioDriver {
+    #[default]
+    Auto,
+    Pulse,
+    Alsa,
+    Oss,
+    Disabled,
+}
+
+//force keeps the cursor inside the window, helps with the camera spinning in NFS World
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum MouseWarpOverride {
+    #[default]
+    Enable,
+    Disable,
+    Force,
+}
+
+//the winecfg settings users usually change for the game, stored in the prefix' registry
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PrefixSettings {
+    pub windows_version: WindowsVersion,
+    pub virtual_desktop: bool,
+    pub desktop_width: u32,
+    pub desktop_height: u32,
+    pub audio_driver: AudioDriver,
+    pub mouse_warp_override: MouseWarpOverride,
+}
@@ -0,0 +1,180 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+//seconds between 1601-01-01 and 1970-01-01, windows FILETIME starts at the former
+const FILETIME_UNIX_OFFSET: u64 = 11_644_473_600;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RegistryFile {
+    //HKEY_LOCAL_MACHINE
+    System,
+    //HKEY_CURRENT_USER
+    User,
+}
+
+impl RegistryFile {
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            RegistryFile::System => "system.reg",
+            RegistryFile::User => "user.reg",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RegValue {
+    //REG_SZ
+    String(String),
+    //REG_EXPAND_SZ, written as str(2):
+    ExpandString(String),
+    //REG_MULTI_SZ, written as str(7):
+    MultiString(Vec<String>),
+    //REG_DWORD
+    Dword(u32),
+    //REG_QWORD, written as hex(b):
+    Qword(u64),
+    //REG_BINARY
+    Binary(Vec<u8>),
+    //any other type or malformed string data, kept byte for byte
+    Raw { kind: u32, data: Vec<u8> },
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RegistryKey {
+    //unescaped and relative to the hive, e.g. Software\Wine\DllOverrides
+    pub path: String,
+    //unix seconds on the key line
+    pub modified: u64,
+    //#time= line, a windows FILETIME
+    pub time: Option<u64>,
+    //#class= and #link lines, kept as they were
+    pub attributes: Vec<String>,
+    //an empty name is the default value, written as @
+    pub values: Vec<(String, RegValue)>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RegistryHive {
+    //lines before the first key, version line, ;; relative to and #arch=
+    pub header: Vec<String>,
+    pub keys: Vec<RegistryKey>,
+}
+
+//registry names are case insensitive, wine keeps whatever case was written first
+fn same_name(a: &str, b: &str) -> bool {
+    a.len() == b.len() && a.to_lowercase() == b.to_lowercase()
+}
+
+fn normalize_path(path: &str) -> &str {
+    path.trim_matches('\\')
+}
+
+impl RegistryKey {
+    pub fn value(&self, name: &str) -> Option<&RegValue> {
+        self.values
+            .iter()
+            .find(|(value_name, _)| same_name(value_name, name))
+            .map(|(_, value)| value)
+    }
+
+    //wine updates both timestamps whenever a key changes
+    fn touch(&mut self) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        self.modified = now.as_secs();
+        self.time = Some(
+            (now.as_secs() + FILETIME_UNIX_OFFSET) * 10_000_000 + now.subsec_nanos() as u64 / 100,
+        );
+    }
+}
+
+impl RegistryHive {
+    pub fn key(&self, path: &str) -> Option<&RegistryKey> {
+        let path = normalize_path(path);
+        self.keys.iter().find(|key| same_name(&key.path, path))
+    }
+
+    //parent keys are not needed, wine creates them while loading
+    fn key_mut_or_insert(&mut self, path: &str) -> &mut RegistryKey {
+        let path = normalize_path(path);
+        let index = match self.keys.iter().position(|key| same_name(&key.path, path)) {
+            Some(index) => index,
+            None => {
+                self.keys.push(RegistryKey {
+                    path: path.to_string(),
+                    ..Default::default()
+                });
+                self.keys.len() - 1
+            }
+        };
+        &mut self.keys[index]
+    }
+
+    pub fn get_value(&self, path: &str, name: &str) -> Option<&RegValue> {
+        self.key(path).and_then(|key| key.value(name))
+    }
+
+    pub fn get_string(&self, path: &str, name: &str) -> Option<&str> {
+        match self.get_value(path, name)? {
+            RegValue::String(value) | RegValue::ExpandString(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn get_dword(&self, path: &str, name: &str) -> Option<u32> {
+        match self.get_value(path, name)? {
+            RegValue::Dword(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn set_value(&mut self, path: &str, name: &str, value: RegValue) {
+        let key = self.key_mut_or_insert(path);
+        match key
+            .values
+            .iter_mut()
+            .find(|(value_name, _)| same_name(value_name, name))
+        {
+            Some((_, existing)) if *existing == value => return,
+            Some((_, existing)) => *existing = value,
+            None => key.values.push((name.to_string(), value)),
+        }
+        key.touch();
+    }
+
+    pub fn set_string(&mut self, path: &str, name: &str, value: &str) {
+        self.set_value(path, name, RegValue::String(value.to_string()));
+    }
+
+    pub fn set_dword(&mut self, path: &str, name: &str, value: u32) {
+        self.set_value(path, name, RegValue::Dword(value));
+    }
+
+    pub fn delete_value(&mut self, path: &str, name: &str) -> bool {
+        let path = normalize_path(path);
+        let Some(key) = self.keys.iter_mut().find(|key| same_name(&key.path, path)) else {
+            return false;
+        };
+
+        let before = key.values.len();
+        key.values
+            .retain(|(value_name, _)| !same_name(value_name, name));
+        if key.values.len() == before {
+            return false;
+        }
+
+        key.touch();
+        true
+    }
+
+    //removes the key together with all of its subkeys
+    pub fn delete_key(&mut self, path: &str) -> bool {
+        let path = normalize_path(path);
+        let prefix = format!("{}\\", path.to_lowercase());
+        let before = self.keys.len();
+        self.keys.retain(|key| {
+            !same_name(&key.path, path) && !key.path.to_lowercase().starts_with(&prefix)
+        });
+        self.keys.len() != before
+    }
+}
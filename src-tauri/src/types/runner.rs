@@ -0,0 +1,92 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum RunnerKind {
+    Wine,
+    Proton,
+}
+
+impl RunnerKind {
+    //folder under runners/ and the folder holding bin/wine inside a runner
+    pub fn runners_dir_name(&self) -> &'static str {
+        match self {
+            RunnerKind::Wine => "wine",
+            RunnerKind::Proton => "proton",
+        }
+    }
+
+    pub fn bin_dir(&self) -> &'static str {
+        match self {
+            RunnerKind::Wine => "bin",
+            RunnerKind::Proton => "files/bin",
+        }
+    }
+}
+
+//where an installed runner or dxvk build was found
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub enum RunnerOrigin {
+    #[default]
+    Utility,
+    Lutris,
+    Steam,
+    Bottles,
+    Heroic,
+    Custom,
+    System,
+}
+
+#[derive(Debug, Clone)]
+pub struct RunnerArchiveInfo {
+    pub kind: RunnerKind,
+    //directory inside the archive that holds the runner, empty when it sits at the top level
+    pub root: PathBuf,
+    pub name: String,
+    pub version: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RunnerSource {
+    pub name: String,
+    //GitHub owner/repo the releases are listed from
+    pub repo: String,
+    pub kind: RunnerKind,
+    //only assets ending with this are offered, releases also carry checksums and other builds
+    pub asset_suffix: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RunnerRelease {
+    pub source: String,
+    pub kind: RunnerKind,
+    pub tag_name: String,
+    pub asset_name: String,
+    pub download_url: String,
+    pub size: u64,
+    //GitHub's "sha256:<hex>" digest of the asset, missing on older releases
+    #[serde(default)]
+    pub digest: Option<String>,
+    pub published_at: Option<String>,
+    pub prerelease: bool,
+    pub installed: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DxvkSource {
+    pub name: String,
+    pub repo: String,
+    pub asset_suffix: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DxvkRelease {
+    pub source: String,
+    pub tag_name: String,
+    pub asset_name: String,
+    pub download_url: String,
+    pub size: u64,
+    pub published_at: Option<String>,
+    pub prerelease: bool,
+    pub installed: bool,
+}
@@ -1,8 +1,17 @@
-use serde::Deserialize;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{config::NetworkComponent, wizard::WizardStep};
 
 #[derive(Debug, Deserialize)]
 pub struct SetupOptions {
     wine_prefix: String,
+    #[serde(default)]
+    resume: bool,
+    //config.json doesn't exist yet, so the wizard passes its own network settings
+    #[serde(default)]
+    network: Option<NetworkComponent>,
     // desktop_shortcut_checked: bool,
     // menu_shortcut_checked: bool
 }
@@ -11,4 +20,39 @@ impl SetupOptions {
     pub fn wine_prefix(&self) -> &str {
         &self.wine_prefix
     }
+
+    pub fn resume(&self) -> bool {
+        self.resume
+    }
+
+    pub fn network(&self) -> Option<&NetworkComponent> {
+        self.network.as_ref()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SetupJournal {
+    pub wine_prefix: String,
+    pub completed_steps: Vec<WizardStep>,
+    pub launcher_path: Option<PathBuf>,
+}
+
+impl SetupJournal {
+    pub fn new(wine_prefix: &str) -> Self {
+        Self {
+            wine_prefix: wine_prefix.to_string(),
+            completed_steps: Vec::new(),
+            launcher_path: None,
+        }
+    }
+
+    pub fn is_completed(&self, step: &WizardStep) -> bool {
+        self.completed_steps.contains(step)
+    }
+
+    pub fn mark_completed(&mut self, step: WizardStep) {
+        if !self.is_completed(&step) {
+            self.completed_steps.push(step);
+        }
+    }
 }
@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+use crate::types::config::D3D9Backend;
+
+//stored next to each snapshot archive, describes the prefix as it was when it was taken
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PrefixSnapshot {
+    pub id: String,
+    pub created_at: u64,
+    pub prefix: String,
+    pub runner: String,
+    pub runner_path: String,
+    pub dxvk_version: String,
+    pub dxvk_enabled: bool,
+    pub d3d9_backend: D3D9Backend,
+    pub include_game_files: bool,
+    pub size: u64,
+}
@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WinetricksVerb {
+    pub name: String,
+    //apps, benchmarks, dlls, fonts or settings, as winetricks groups them
+    pub category: String,
+    pub description: String,
+    pub installed: bool,
+}
@@ -1,6 +1,6 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub enum WizardStep {
     Cleanup,
     SetupFolders,
@@ -14,4 +14,5 @@ pub enum WizardStep {
     ValidateInstallation,
     Complete,
     Failed,
+    Cancelled,
 }
@@ -0,0 +1,28 @@
+use tauri::{path::BaseDirectory, AppHandle, Manager};
+
+use crate::{
+    types::{cache::CacheInfo, error::CustomError},
+    utils::cache::{clear_cache, get_cache_info},
+};
+
+#[tauri::command]
+pub fn get_download_cache(app: AppHandle) -> Result<CacheInfo, CustomError> {
+    let cache_dir = app
+        .path()
+        .resolve("sbrw-utility/cache", BaseDirectory::Data)?;
+
+    get_cache_info(&cache_dir)
+}
+
+#[tauri::command]
+pub fn clear_download_cache(app: AppHandle) -> Result<(), CustomError> {
+    let cache_dir = app
+        .path()
+        .resolve("sbrw-utility/cache", BaseDirectory::Data)?;
+
+    clear_cache(&cache_dir)?;
+
+    log::info!("Download cache cleared");
+
+    Ok(())
+}
@@ -5,21 +5,45 @@ use std::{
 
 use anyhow::anyhow;
 
-use log::{error, info};
+use log::{error, info, warn};
 use tauri::{path::BaseDirectory, AppHandle, Manager, Window};
 use which::which;
 
 use crate::{
-    types::error::CustomError,
+    types::{config::D3D9Backend, error::CustomError},
     utility::validate::validate_on_startup,
     utils::{
         emitter::report_event,
         initialize::{command_is_available, get_config},
+        runner::{
+            prepare_compat_data, resolve_proton_root, resolve_proton_script, resolve_wine_binary,
+            resolve_wineserver,
+        },
     },
 };
 
+//umu wants some game id, 0 means no protonfixes
+const UMU_GAME_ID: &str = "0";
+const STEAM_CLIENT_LOCATIONS: [&str; 2] = [".steam/steam", ".local/share/Steam"];
+
+enum LaunchBackend {
+    Wine(PathBuf),
+    Proton(PathBuf),
+    Umu(PathBuf),
+}
+
+fn path_to_string(path: &Path) -> Result<String, CustomError> {
+    path.to_str()
+        .map(|path| path.to_string())
+        .ok_or_else(|| CustomError::Anyhow(anyhow!("Cannot convert wine path to string")))
+}
+
+fn bool_flag(enabled: bool) -> String {
+    if enabled { "1" } else { "0" }.to_string()
+}
+
 #[tauri::command]
-pub async fn check_config() -> Result<bool, CustomError> {
+pub async fn check_config(window: Window) -> Result<bool, CustomError> {
     let config = match get_config() {
         Ok(cfg) => cfg,
         Err(_) => return Ok(false),
@@ -29,9 +53,17 @@ pub async fn check_config() -> Result<bool, CustomError> {
     let wine_path = PathBuf::from(config.wine.path);
     let dxvk_path = PathBuf::from(config.dxvk.path);
     let dxvk_version = config.dxvk.version;
+    let dxvk_enabled = config.dxvk.enabled;
 
     let result = tauri::async_runtime::spawn_blocking(move || {
-        validate_on_startup(&wine_prefix, &wine_path, &dxvk_path, &dxvk_version)
+        validate_on_startup(
+            &wine_prefix,
+            &wine_path,
+            &dxvk_path,
+            &dxvk_version,
+            dxvk_enabled,
+            &window,
+        )
     })
     .await
     .map_err(|e| CustomError::Anyhow(anyhow!("Task join error: {}", e)))?;
@@ -47,33 +79,46 @@ pub fn launch_game(window: Window) -> Result<(), CustomError> {
 
     let mut default_wine_overrides: Vec<String> = vec!["winemenubuilder=".to_string()];
 
-    let wine_cmd_path = Path::new(&config.wine.path).join("wine");
-    let wine_cmd_str = wine_cmd_path
-        .to_str()
-        .ok_or_else(|| CustomError::Anyhow(anyhow!("Cannot convert wine path to string")))?;
+    let backend = match resolve_proton_root(Path::new(&config.wine.path)) {
+        Some(proton_root) if command_is_available("umu-run")? => LaunchBackend::Umu(proton_root),
+        Some(proton_root) => LaunchBackend::Proton(proton_root),
+        None => LaunchBackend::Wine(resolve_wine_binary(Path::new(&config.wine.path))),
+    };
     let dxvk_enabled = config.dxvk.enabled;
+    let d3d9_backend = config.d3d9.backend;
     let game_dir = config.game.directory;
     let launcher_path = config.game.launcher;
     let feral_gamemode = config.wine.feral_gamemode;
     let is_feral_gamemode_available = command_is_available("gamemoderun")?;
     let mangohud = config.game.mangohud;
     let is_mangohud_available = command_is_available("mangohud")?;
-    let esync = if config.wine.esync { "1" } else { "0" };
-    let fsync = if config.wine.fsync { "1" } else { "0" };
+    let esync = config.wine.esync;
+    let fsync = config.wine.fsync;
     let wine_prefix = config.game.prefix;
     let provided_overrides = config.game.dll_overrides;
     let environment_variables = config.game.environment_variables;
 
     let mut wine_dll_overrides: Vec<String> = Vec::new();
 
-    wine_dll_overrides.push(
-        if dxvk_enabled {
-            "d3d9,d3d10,d3d10_1,d3d10core,d3d11,dxgi=n"
-        } else {
-            "d3d9,d3d10,d3d10_1,d3d10core,d3d11,dxgi=b"
+    //proton ships its own dxvk and picks the d3d dlls itself
+    match backend {
+        LaunchBackend::Wine(_) => {
+            let native_d3d9 = match d3d9_backend {
+                D3D9Backend::Wined3d => false,
+                D3D9Backend::Dxvk => dxvk_enabled,
+                D3D9Backend::Nine => true,
+            };
+            wine_dll_overrides.push(format!("d3d9={}", if native_d3d9 { "n" } else { "b" }));
+            wine_dll_overrides.push(format!(
+                "d3d10,d3d10_1,d3d10core,d3d11,dxgi={}",
+                if dxvk_enabled { "n" } else { "b" }
+            ));
         }
-        .to_string(),
-    );
+        _ if d3d9_backend == D3D9Backend::Nine => {
+            warn!("Gallium Nine is not supported with Proton runners, using Proton's d3d9");
+        }
+        _ => {}
+    }
 
     for override_var in provided_overrides {
         wine_dll_overrides.push(format!("{}={}", override_var.key, override_var.value));
@@ -83,22 +128,25 @@ pub fn launch_game(window: Window) -> Result<(), CustomError> {
 
     let wine_dll_overrides_str = wine_dll_overrides.join(";");
 
-    let command = if is_mangohud_available && mangohud {
-        "mangohud"
-    } else if feral_gamemode && is_feral_gamemode_available {
-        "gamemoderun"
-    } else {
-        wine_cmd_str
+    let mut runner_command: Vec<String> = match &backend {
+        LaunchBackend::Wine(wine_cmd_path) => vec![path_to_string(wine_cmd_path)?],
+        LaunchBackend::Proton(proton_root) => vec![
+            path_to_string(&resolve_proton_script(proton_root))?,
+            "run".to_string(),
+        ],
+        LaunchBackend::Umu(_) => vec!["umu-run".to_string()],
     };
 
     let mut arguments: Vec<String> = Vec::new();
     if is_mangohud_available && mangohud {
+        arguments.push("mangohud".to_string());
         arguments.push("gamemoderun".to_string());
-        arguments.push(wine_cmd_str.to_string());
     } else if feral_gamemode && is_feral_gamemode_available {
-        arguments.push(wine_cmd_str.to_string());
+        arguments.push("gamemoderun".to_string());
     }
+    arguments.append(&mut runner_command);
     arguments.push(launcher_path);
+    let command = arguments.remove(0);
 
     let mut envs: Vec<(String, String)> = Vec::new();
 
@@ -106,9 +154,57 @@ pub fn launch_game(window: Window) -> Result<(), CustomError> {
         envs.push((env.key, env.value));
     }
 
-    envs.push(("WINEPREFIX".to_string(), wine_prefix.to_string()));
-    envs.push(("WINEESYNC".to_string(), esync.to_string()));
-    envs.push(("WINEFSYNC".to_string(), fsync.to_string()));
+    match &backend {
+        LaunchBackend::Wine(_) => {
+            envs.push(("WINEPREFIX".to_string(), wine_prefix.to_string()));
+            envs.push(("WINEESYNC".to_string(), bool_flag(esync)));
+            envs.push(("WINEFSYNC".to_string(), bool_flag(fsync)));
+        }
+        LaunchBackend::Proton(_) => {
+            let compat_root = window
+                .path()
+                .resolve("sbrw-utility/compatdata", BaseDirectory::Data)?;
+            let compat_data = prepare_compat_data(&compat_root, Path::new(&wine_prefix))?;
+            //proton only reads the client path, an empty value is fine without steam
+            let steam_client = window
+                .path()
+                .home_dir()
+                .ok()
+                .and_then(|home| {
+                    STEAM_CLIENT_LOCATIONS
+                        .iter()
+                        .map(|location| home.join(location))
+                        .find(|path| path.is_dir())
+                })
+                .map(|path| path.display().to_string())
+                .unwrap_or_default();
+
+            envs.push((
+                "STEAM_COMPAT_DATA_PATH".to_string(),
+                compat_data.display().to_string(),
+            ));
+            envs.push(("STEAM_COMPAT_CLIENT_INSTALL_PATH".to_string(), steam_client));
+        }
+        LaunchBackend::Umu(proton_root) => {
+            //umu derives the compat data path from WINEPREFIX on its own
+            envs.push(("WINEPREFIX".to_string(), wine_prefix.to_string()));
+            envs.push(("PROTONPATH".to_string(), proton_root.display().to_string()));
+            envs.push(("GAMEID".to_string(), UMU_GAME_ID.to_string()));
+        }
+    }
+
+    if !matches!(backend, LaunchBackend::Wine(_)) {
+        if !esync {
+            envs.push(("PROTON_NO_ESYNC".to_string(), "1".to_string()));
+        }
+        if !fsync {
+            envs.push(("PROTON_NO_FSYNC".to_string(), "1".to_string()));
+        }
+        if !dxvk_enabled {
+            envs.push(("PROTON_USE_WINED3D".to_string(), "1".to_string()));
+        }
+    }
+
     envs.push(("WINEDLLOVERRIDES".to_string(), wine_dll_overrides_str));
 
     info!("Launching game");
@@ -135,7 +231,7 @@ pub fn launch_game(window: Window) -> Result<(), CustomError> {
         })?;
 
     let window_ = window.clone();
-    let wine_cmd_ = config.wine.path.clone();
+    let wineserver_cmd = resolve_wineserver(Path::new(&config.wine.path));
     let wine_prefix_ = wine_prefix.clone();
 
     std::thread::spawn(move || {
@@ -145,11 +241,10 @@ pub fn launch_game(window: Window) -> Result<(), CustomError> {
             report_event(&window, "game-state", "GameRunning")?;
             info!("Game is running");
 
-            let wineserver_wait_status =
-                std::process::Command::new(Path::new(&wine_cmd_).join("wineserver"))
-                    .arg("-w")
-                    .env("WINEPREFIX", wine_prefix_)
-                    .status()?;
+            let wineserver_wait_status = std::process::Command::new(wineserver_cmd)
+                .arg("-w")
+                .env("WINEPREFIX", wine_prefix_)
+                .status()?;
 
             report_event(&window_, "game-state", "GameStopped")?;
             info!("Game exited with: {:?}", wineserver_wait_status);
@@ -169,7 +264,8 @@ pub fn launch_game(window: Window) -> Result<(), CustomError> {
 pub fn stop_game() -> Result<(), CustomError> {
     let config = get_config()?;
 
-    let wineserver_cmd = Path::new(&config.wine.path).join("wineserver");
+    //system wine may keep wineserver outside its bin folder
+    let wineserver_cmd = resolve_wineserver(Path::new(&config.wine.path));
     let wine_prefix = config.game.prefix;
 
     std::process::Command::new(wineserver_cmd)
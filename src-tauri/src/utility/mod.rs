@@ -1,4 +1,8 @@
+pub mod cache;
 pub mod launcher;
 pub mod repair;
+pub mod runner;
 pub mod setting;
+pub mod snapshot;
 pub mod validate;
+pub mod winetricks;
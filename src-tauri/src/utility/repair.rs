@@ -1,44 +1,109 @@
 use std::{fs, path::Path};
 
+use tauri::{path::BaseDirectory, Manager, Window};
+
 use crate::{
     setup::{
-        dxvk::setup_dxvk, finalize::extract_to_game_dir, resource::download_game_launcher,
-        validate::validate_installation, wine::initialize_wine_environment,
+        dxvk::setup_dxvk,
+        finalize::extract_to_game_dir,
+        nine::setup_nine,
+        prefix::sync_dll_overrides,
+        resource::download_game_launcher,
+        validate::validate_installation,
+        wine::{initialize_wine_environment, release_prefix},
     },
     types::error::{CustomError, ValidationError},
     utils::{
-        downloader::{build_client, download_using_url},
-        fs::{cleanup_file, extract_archive},
+        cancel::CancellationToken,
+        downloader::build_client,
+        dxvk::{download_dxvk, find_dxvk_release},
+        fs::cleanup_file,
+        initialize::{
+            get_config, get_d3d9_settings, get_dxvk_install_mode, get_network_settings,
+            get_persisted_dll_overrides, get_runner_settings, update_config,
+        },
+        nine::ensure_nine_build,
+        runner::runner_bin_dir,
     },
 };
 
-fn repair_game_launcher(game_dir: &Path) -> Result<(), CustomError> {
+fn repair_game_launcher(game_dir: &Path, window: &Window) -> Result<(), CustomError> {
     fs::create_dir_all(game_dir)?;
 
-    let launcher_path = download_game_launcher(game_dir)?;
+    let launcher_path = download_game_launcher(game_dir, &get_network_settings(), window, None)?;
     extract_to_game_dir(game_dir, &launcher_path)?;
     cleanup_file(&launcher_path)?;
 
     Ok(())
 }
 
-fn repair_dxvk(dxvk_version: &str, dxvk_path: &Path, wine_prefix: &str) -> Result<(), CustomError> {
-    let modified_version_str = dxvk_version.replace("dxvk-", "v");
-    let dxvk_url = format!(
-        "https://github.com/doitsujin/dxvk/releases/download/{}/{}.tar.gz",
-        modified_version_str, dxvk_version
-    );
-    let dxvk_file_name = format!("{}.tar.gz", dxvk_version);
-
+fn repair_dxvk(
+    dxvk_version: &str,
+    dxvk_path: &Path,
+    wine_prefix: &str,
+    wine_path: &Path,
+    window: &Window,
+) -> Result<(), CustomError> {
     if !dxvk_path.exists() {
-        fs::create_dir_all(dxvk_path)?;
-        let client = build_client()?;
-        let dxvk_archive_path = download_using_url(&client, &dxvk_url, dxvk_path, &dxvk_file_name)?;
-        extract_archive(&dxvk_archive_path, dxvk_path)?;
-        cleanup_file(&dxvk_archive_path)?;
+        let app_dir = window.path().resolve("sbrw-utility", BaseDirectory::Data)?;
+        let dxvk_dir = dxvk_path
+            .parent()
+            .ok_or_else(|| CustomError::PathError("Invalid dxvk path".to_string()))?;
+        let client = build_client(&get_network_settings(), &app_dir.join("cache"))?;
+
+        //the build may come from any of the dxvk sources, e.g. dxvk-async
+        let release = find_dxvk_release(
+            &client,
+            &get_runner_settings().dxvk_sources,
+            dxvk_dir,
+            dxvk_version,
+        )?
+        .ok_or_else(|| {
+            CustomError::PathError(format!(
+                "No release of {} found in the dxvk sources",
+                dxvk_version
+            ))
+        })?;
+
+        download_dxvk(
+            &client,
+            &release,
+            dxvk_dir,
+            &app_dir.join("runners/downloads"),
+            window,
+        )?;
+    }
+
+    release_prefix(wine_prefix, wine_path)?;
+    setup_dxvk(
+        wine_prefix,
+        dxvk_path,
+        get_dxvk_install_mode(),
+        get_d3d9_settings().backend,
+    )?;
+
+    Ok(())
+}
+
+fn repair_nine(wine_prefix: &str, wine_path: &Path, window: &Window) -> Result<(), CustomError> {
+    let app_dir = window.path().resolve("sbrw-utility", BaseDirectory::Data)?;
+    let mut config = get_config()?;
+
+    //the build may have been removed along with the runtime folder
+    if ensure_nine_build(&mut config.d3d9, &app_dir, window)? {
+        update_config(config.clone(), &app_dir.join("config.json"))?;
     }
 
-    setup_dxvk(wine_prefix, dxvk_path)?;
+    release_prefix(wine_prefix, wine_path)?;
+    setup_nine(wine_prefix, Path::new(&config.d3d9.nine_path))?;
+
+    Ok(())
+}
+
+fn repair_dll_overrides(wine_prefix: &str, wine_path: &Path) -> Result<(), CustomError> {
+    //a wineserver left from the last run would write its old registry back on exit
+    release_prefix(wine_prefix, wine_path)?;
+    sync_dll_overrides(Path::new(wine_prefix), &get_persisted_dll_overrides(), &[])?;
 
     Ok(())
 }
@@ -49,23 +114,27 @@ pub fn attempt_repair(
     wine_path: &Path,
     dxvk_path: &Path,
     dxvk_version: &str,
+    dxvk_enabled: bool,
+    window: &Window,
 ) -> Result<(), CustomError> {
-    let wineboot_path = &wine_path.join("wineboot");
+    let wineboot_path = &runner_bin_dir(wine_path).join("wineboot");
     let wine_prefix_path = Path::new(wine_prefix);
     let game_dir = wine_prefix_path.join("drive_c/SBRW");
 
     for err in errors {
         match err {
             ValidationError::MissingWinePrefix => {
-                initialize_wine_environment(wineboot_path, wine_prefix)?
+                initialize_wine_environment(wineboot_path, wine_prefix, &CancellationToken::new())?
             }
-            ValidationError::MissingGameLauncher => repair_game_launcher(&game_dir)?,
+            ValidationError::MissingGameLauncher => repair_game_launcher(&game_dir, window)?,
             ValidationError::MissingDXVKSymlinks => {
-                repair_dxvk(dxvk_version, dxvk_path, wine_prefix)?
+                repair_dxvk(dxvk_version, dxvk_path, wine_prefix, wine_path, window)?
             }
+            ValidationError::MissingGalliumNine => repair_nine(wine_prefix, wine_path, window)?,
+            ValidationError::DllOverridesDrift => repair_dll_overrides(wine_prefix, wine_path)?,
         }
     }
 
-    validate_installation(wine_prefix, wine_path, dxvk_path)?;
+    validate_installation(wine_prefix, wine_path, dxvk_path, dxvk_enabled)?;
     Ok(())
 }
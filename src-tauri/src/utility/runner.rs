@@ -0,0 +1,128 @@
+use anyhow::anyhow;
+use tauri::{path::BaseDirectory, AppHandle, Manager, Window};
+
+use crate::{
+    types::{
+        config::{RunnerVersion, RuntimeVersion},
+        error::CustomError,
+        runner::{DxvkRelease, RunnerOrigin, RunnerRelease},
+    },
+    utils::{
+        downloader::build_client,
+        dxvk::{download_dxvk, list_dxvk_releases},
+        emitter::{report_download_progress, report_extract_progress},
+        initialize::{get_network_settings, get_runner_settings},
+        runner::{download_runner, list_runner_releases},
+    },
+};
+
+#[tauri::command]
+pub async fn get_runner_releases(app: AppHandle) -> Result<Vec<RunnerRelease>, CustomError> {
+    let app_dir = app.path().resolve("sbrw-utility", BaseDirectory::Data)?;
+    let cache_dir = app_dir.join("cache");
+    let runners_dir = app_dir.join("runners");
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let settings = get_runner_settings();
+        let client = build_client(&get_network_settings(), &cache_dir)?;
+
+        list_runner_releases(
+            &client,
+            &settings.sources,
+            settings.releases_per_source,
+            &runners_dir,
+        )
+    })
+    .await
+    .map_err(|e| CustomError::Anyhow(anyhow!("Task join error: {}", e)))?
+}
+
+#[tauri::command]
+pub async fn install_runner(
+    release: RunnerRelease,
+    app: AppHandle,
+    window: Window,
+) -> Result<RunnerVersion, CustomError> {
+    let app_dir = app.path().resolve("sbrw-utility", BaseDirectory::Data)?;
+    let cache_dir = app_dir.join("cache");
+    let runners_dir = app_dir.join("runners");
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let client = build_client(&get_network_settings(), &cache_dir)?;
+        let runner = download_runner(
+            &client,
+            &get_runner_settings(),
+            &release,
+            &runners_dir,
+            &mut |progress| {
+                report_download_progress(&window, progress).ok();
+            },
+            &mut |progress| {
+                report_extract_progress(&window, progress).ok();
+            },
+        )?;
+
+        let runner_dir = runners_dir
+            .join(runner.kind.runners_dir_name())
+            .join(&runner.name);
+
+        log::info!("Installed runner {} from {}", runner.name, release.source);
+
+        Ok(RunnerVersion {
+            name: runner.name,
+            path: runner_dir.join(runner.kind.bin_dir()).display().to_string(),
+            version: runner.version,
+            origin: RunnerOrigin::Utility,
+        })
+    })
+    .await
+    .map_err(|e| CustomError::Anyhow(anyhow!("Task join error: {}", e)))?
+}
+
+#[tauri::command]
+pub async fn get_dxvk_releases(app: AppHandle) -> Result<Vec<DxvkRelease>, CustomError> {
+    let app_dir = app.path().resolve("sbrw-utility", BaseDirectory::Data)?;
+    let cache_dir = app_dir.join("cache");
+    let dxvk_dir = app_dir.join("runtime/dxvk");
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let settings = get_runner_settings();
+        let client = build_client(&get_network_settings(), &cache_dir)?;
+
+        list_dxvk_releases(
+            &client,
+            &settings.dxvk_sources,
+            settings.releases_per_source,
+            &dxvk_dir,
+        )
+    })
+    .await
+    .map_err(|e| CustomError::Anyhow(anyhow!("Task join error: {}", e)))?
+}
+
+#[tauri::command]
+pub async fn install_dxvk(
+    release: DxvkRelease,
+    app: AppHandle,
+    window: Window,
+) -> Result<RuntimeVersion, CustomError> {
+    let app_dir = app.path().resolve("sbrw-utility", BaseDirectory::Data)?;
+    let cache_dir = app_dir.join("cache");
+    let dxvk_dir = app_dir.join("runtime/dxvk");
+    let downloads_dir = app_dir.join("runners/downloads");
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let client = build_client(&get_network_settings(), &cache_dir)?;
+        let name = download_dxvk(&client, &release, &dxvk_dir, &downloads_dir, &window)?;
+
+        log::info!("Installed dxvk {} from {}", name, release.source);
+
+        Ok(RuntimeVersion {
+            path: dxvk_dir.join(&name).display().to_string(),
+            name,
+            origin: RunnerOrigin::Utility,
+        })
+    })
+    .await
+    .map_err(|e| CustomError::Anyhow(anyhow!("Task join error: {}", e)))?
+}
@@ -1,24 +1,44 @@
 use std::{fs, path::PathBuf};
 
 use anyhow::anyhow;
-use log::info;
-use std::path::Path;
-use tauri::{path::BaseDirectory, AppHandle, Manager};
+use log::{info, warn};
+use std::path::{Component, Path};
+use tauri::{path::BaseDirectory, AppHandle, Manager, Window};
 
 use crate::{
+    setup::{
+        dxvk::{read_dxvk_state, switch_dxvk, uninstall_dxvk},
+        nine::{find_d3dadapter, is_nine_linked, remove_nine, setup_nine},
+        prefix::{
+            dll_overrides_changed, read_prefix_settings, sync_dll_overrides, write_prefix_settings,
+        },
+        validate::check_nine,
+        wine::release_prefix,
+    },
     types::{
-        config::{Config, RunnerVersion, RuntimeVersion},
+        config::{Config, D3D9Backend, RunnerVersion, RuntimeVersion},
         error::CustomError,
+        prefix::{PrefixDxvkState, PrefixSettings},
+        runner::{RunnerKind, RunnerOrigin},
     },
     utils::{
-        fs::extract_archive,
+        emitter::report_extract_progress,
+        fs::dir_size,
         initialize::{command_is_available, get_config, update_config},
+        nine::ensure_nine_build,
+        process::is_path_in_use,
+        runner::{
+            detect_system_runners, install_runner_archive, read_installed_runner_version,
+            resolve_proton_root,
+        },
+        search_paths::{dxvk_search_paths, runner_search_paths},
     },
 };
 
 fn get_available_wine_directories(
     wine_dir: &Path,
     proton_dir: &Path,
+    extra_dirs: &[(PathBuf, RunnerOrigin)],
 ) -> Result<Vec<RunnerVersion>, CustomError> {
     if !wine_dir.is_dir() || !proton_dir.is_dir() {
         return Err(CustomError::PathError(format!(
@@ -28,48 +48,94 @@ fn get_available_wine_directories(
 
     let mut wine_versions: Vec<RunnerVersion> = Vec::new();
 
-    let mut collect_versions = |dir: &Path, marker: &str| -> Result<(), CustomError> {
-        for entry in fs::read_dir(dir)? {
-            let entry = entry?;
-            let path = entry.path();
+    let mut collect_versions =
+        |dir: &Path, kinds: &[RunnerKind], origin: RunnerOrigin| -> Result<(), CustomError> {
+            for entry in fs::read_dir(dir)? {
+                let entry = entry?;
+                let path = entry.path();
+
+                let kind = match kinds
+                    .iter()
+                    .find(|kind| path.join(kind.bin_dir()).join("wine").exists())
+                {
+                    Some(kind) => *kind,
+                    None => continue,
+                };
 
-            if path.is_dir() && path.join(marker).join("wine").exists() {
                 if let Some(name) = path.file_name().and_then(|s| s.to_str()) {
+                    //the name identifies a version in the settings, so the first one found wins
+                    if wine_versions.iter().any(|v| v.name == name) {
+                        continue;
+                    }
+
                     wine_versions.push(RunnerVersion {
                         name: name.to_string(),
-                        path: path.join(marker).display().to_string(),
+                        path: path.join(kind.bin_dir()).display().to_string(),
+                        version: read_installed_runner_version(&path, kind),
+                        origin,
                     });
                 }
             }
+            Ok(())
+        };
+
+    collect_versions(wine_dir, &[RunnerKind::Wine], RunnerOrigin::Utility)?;
+    collect_versions(proton_dir, &[RunnerKind::Proton], RunnerOrigin::Utility)?;
+
+    //other launchers' folders may be unreadable or half removed, that shouldn't break settings
+    for (dir, origin) in extra_dirs {
+        if let Err(err) = collect_versions(dir, &[RunnerKind::Wine, RunnerKind::Proton], *origin) {
+            warn!("Skipping runner search path {}: {}", dir.display(), err);
         }
-        Ok(())
-    };
+    }
 
-    collect_versions(wine_dir, "bin")?;
-    collect_versions(proton_dir, "files/bin")?;
+    for runner in detect_system_runners() {
+        if !wine_versions.iter().any(|v| v.name == runner.name) {
+            wine_versions.push(runner);
+        }
+    }
 
     Ok(wine_versions)
 }
 
-fn get_available_dxvk_directories(dxvk_dir: &Path) -> Result<Vec<RuntimeVersion>, CustomError> {
+fn get_available_dxvk_directories(
+    dxvk_dir: &Path,
+    extra_dirs: &[(PathBuf, RunnerOrigin)],
+) -> Result<Vec<RuntimeVersion>, CustomError> {
     if !dxvk_dir.exists() || !dxvk_dir.is_dir() {
         return Err(CustomError::PathError(format!("dxvk directory not found")));
     }
 
     let mut dxvk_versions: Vec<RuntimeVersion> = Vec::new();
 
-    for entry in fs::read_dir(dxvk_dir)? {
-        let entry = entry?;
-        let path = entry.path();
+    let mut collect_versions = |dir: &Path, origin: RunnerOrigin| -> Result<(), CustomError> {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let path = entry.path();
+
+            if path.is_dir() && path.join("x32").exists() && path.join("x64").exists() {
+                if let Some(name) = path.file_name().and_then(|s| s.to_str()) {
+                    if dxvk_versions.iter().any(|v| v.name == name) {
+                        continue;
+                    }
 
-        if path.is_dir() && path.join("x32").exists() && path.join("x64").exists() {
-            if let Some(name) = path.file_name().and_then(|s| s.to_str()) {
-                dxvk_versions.push(RuntimeVersion {
-                    name: name.to_string(),
-                    path: path.display().to_string(),
-                })
+                    dxvk_versions.push(RuntimeVersion {
+                        name: name.to_string(),
+                        path: path.display().to_string(),
+                        origin,
+                    })
+                }
             }
         }
+        Ok(())
+    };
+
+    collect_versions(dxvk_dir, RunnerOrigin::Utility)?;
+
+    for (dir, origin) in extra_dirs {
+        if let Err(err) = collect_versions(dir, *origin) {
+            warn!("Skipping dxvk search path {}: {}", dir.display(), err);
+        }
     }
 
     Ok(dxvk_versions)
@@ -79,13 +145,19 @@ fn get_available_dxvk_directories(dxvk_dir: &Path) -> Result<Vec<RuntimeVersion>
 pub fn populate_settings(app: AppHandle) -> Result<Config, CustomError> {
     let app_dir = app.path().resolve("sbrw-utility", BaseDirectory::Data)?;
 
+    let home_dir = app.path().home_dir()?;
+
     let mut config_string = get_config()?;
 
     let wine_versions = get_available_wine_directories(
         &app_dir.join("runners/wine"),
         &app_dir.join("runners/proton"),
+        &runner_search_paths(&home_dir, &config_string.search_paths),
+    )?;
+    let dxvk_versions = get_available_dxvk_directories(
+        &app_dir.join("runtime/dxvk"),
+        &dxvk_search_paths(&home_dir, &config_string.search_paths),
     )?;
-    let dxvk_versions = get_available_dxvk_directories(&app_dir.join("runtime/dxvk"))?;
 
     config_string.available_wine_versions = Some(wine_versions);
     config_string.available_dxvk_versions = Some(dxvk_versions);
@@ -93,12 +165,117 @@ pub fn populate_settings(app: AppHandle) -> Result<Config, CustomError> {
     Ok(config_string)
 }
 
+//the prefix keeps its d3d dlls until they are switched or removed here
+fn apply_prefix_d3d(
+    config: &mut Config,
+    previous_config: &Config,
+    app_dir: &Path,
+    window: &Window,
+) -> Result<(), CustomError> {
+    let wine_prefix = config.game.prefix.clone();
+    if !Path::new(&wine_prefix).join("drive_c").is_dir() {
+        return Ok(());
+    }
+
+    //proton copies its own dxvk into the prefix on every launch, the dlls are its to manage
+    if resolve_proton_root(Path::new(&config.wine.path)).is_some() {
+        info!("Proton runner configured, leaving the prefix's d3d dlls to it");
+        return Ok(());
+    }
+
+    let d3d9_backend = config.d3d9.backend;
+    let leaving_proton = resolve_proton_root(Path::new(&previous_config.wine.path)).is_some();
+
+    if d3d9_backend == D3D9Backend::Nine {
+        //failing before the download when mesa can't run nine anyway
+        find_d3dadapter()?;
+        ensure_nine_build(&mut config.d3d9, app_dir, window)?;
+    }
+
+    let dxvk_state = read_dxvk_state(&wine_prefix);
+
+    let remove_nine_links = d3d9_backend != D3D9Backend::Nine && is_nine_linked(&wine_prefix);
+    let remove_dxvk = !config.dxvk.enabled && dxvk_state.installed;
+    let switch_dxvk_build = config.dxvk.enabled
+        && (config.dxvk.path != previous_config.dxvk.path
+            || !dxvk_state.installed
+            || config.dxvk.install_mode != dxvk_state.install_mode
+            || d3d9_backend != previous_config.d3d9.backend
+            || leaving_proton);
+    let link_nine = d3d9_backend == D3D9Backend::Nine
+        && (config.d3d9.nine_path != previous_config.d3d9.nine_path
+            || check_nine(Path::new(&wine_prefix)).is_err());
+
+    //untouched dlls shouldn't need the game closed to save other settings
+    if !remove_nine_links && !remove_dxvk && !switch_dxvk_build && !link_nine {
+        return Ok(());
+    }
+
+    //dlls must not be replaced under a running game, the wineserver is the previous runner's
+    release_prefix(&wine_prefix, Path::new(&previous_config.wine.path))?;
+
+    if remove_nine_links {
+        remove_nine(&wine_prefix)?;
+    }
+
+    if remove_dxvk {
+        uninstall_dxvk(&wine_prefix, Path::new(&config.wine.path))?;
+    } else if switch_dxvk_build {
+        switch_dxvk(
+            &wine_prefix,
+            Path::new(&previous_config.dxvk.path),
+            Path::new(&config.dxvk.path),
+            config.dxvk.install_mode,
+            d3d9_backend,
+        )?;
+    }
+
+    if link_nine {
+        setup_nine(&wine_prefix, Path::new(&config.d3d9.nine_path))?;
+    }
+
+    Ok(())
+}
+
+fn apply_dll_overrides(config: &Config, previous_config: &Config) -> Result<(), CustomError> {
+    let wine_prefix = Path::new(&config.game.prefix);
+    if !wine_prefix.join("user.reg").is_file() {
+        return Ok(());
+    }
+
+    let overrides = config.game.persisted_dll_overrides();
+    let previous_overrides = previous_config.game.persisted_dll_overrides();
+
+    //untouched overrides shouldn't need the game closed to save other settings
+    if !dll_overrides_changed(wine_prefix, overrides, previous_overrides)? {
+        return Ok(());
+    }
+
+    //the wineserver still running belongs to the previous runner
+    release_prefix(&config.game.prefix, Path::new(&previous_config.wine.path))?;
+    sync_dll_overrides(wine_prefix, overrides, previous_overrides)
+}
+
 #[tauri::command]
-pub fn save_settings(config: Config, app: AppHandle) -> Result<bool, CustomError> {
+pub async fn save_settings(
+    config: Config,
+    app: AppHandle,
+    window: Window,
+) -> Result<bool, CustomError> {
     let app_dir = app.path().resolve("sbrw-utility", BaseDirectory::Data)?;
 
     let config_path = app_dir.join("config.json");
 
+    let previous_config = get_config()?;
+    let config = tauri::async_runtime::spawn_blocking(move || {
+        let mut config = config;
+        apply_prefix_d3d(&mut config, &previous_config, &app_dir, &window)?;
+        apply_dll_overrides(&config, &previous_config)?;
+        Ok::<_, CustomError>(config)
+    })
+    .await
+    .map_err(|e| CustomError::Anyhow(anyhow!("Task join error: {}", e)))??;
+
     update_config(config, &config_path)?;
 
     log::info!("config.json updated");
@@ -107,54 +284,158 @@ pub fn save_settings(config: Config, app: AppHandle) -> Result<bool, CustomError
 }
 
 #[tauri::command]
-pub async fn add_wine_version(selected_path: &Path, app: AppHandle) -> Result<(), CustomError> {
+pub fn get_prefix_dxvk_state() -> Result<PrefixDxvkState, CustomError> {
+    Ok(read_dxvk_state(&get_config()?.game.prefix))
+}
+
+#[tauri::command]
+pub async fn remove_dxvk_from_prefix(app: AppHandle) -> Result<PrefixDxvkState, CustomError> {
     let app_dir = app.path().resolve("sbrw-utility", BaseDirectory::Data)?;
+    let config_path = app_dir.join("config.json");
+
+    let mut config = get_config()?;
+    let wine_prefix = config.game.prefix.clone();
+    let wine_path = PathBuf::from(&config.wine.path);
+
+    let state = tauri::async_runtime::spawn_blocking(move || {
+        release_prefix(&wine_prefix, &wine_path)?;
+        uninstall_dxvk(&wine_prefix, &wine_path)?;
+        Ok::<_, CustomError>(read_dxvk_state(&wine_prefix))
+    })
+    .await
+    .map_err(|e| CustomError::Anyhow(anyhow!("Task join error: {}", e)))??;
 
+    //launching with dxvk overrides would now point at missing native dlls
+    config.dxvk.enabled = false;
+    update_config(config, &config_path)?;
+
+    Ok(state)
+}
+
+#[tauri::command]
+pub fn get_prefix_settings() -> Result<PrefixSettings, CustomError> {
+    read_prefix_settings(Path::new(&get_config()?.game.prefix))
+}
+
+#[tauri::command]
+pub async fn set_prefix_settings(settings: PrefixSettings) -> Result<PrefixSettings, CustomError> {
+    let config = get_config()?;
+    let wine_prefix = config.game.prefix;
+    let wine_path = PathBuf::from(&config.wine.path);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let wine_prefix_path = Path::new(&wine_prefix);
+        release_prefix(&wine_prefix, &wine_path)?;
+        write_prefix_settings(wine_prefix_path, &settings)?;
+
+        read_prefix_settings(wine_prefix_path)
+    })
+    .await
+    .map_err(|e| CustomError::Anyhow(anyhow!("Task join error: {}", e)))?
+}
+
+#[tauri::command]
+pub async fn add_wine_version(
+    selected_path: &Path,
+    app: AppHandle,
+    window: Window,
+) -> Result<(), CustomError> {
+    let app_dir = app.path().resolve("sbrw-utility", BaseDirectory::Data)?;
+
+    let runners_dir = app_dir.join("runners");
+    let selected_path = selected_path.to_owned();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        install_runner_archive(&selected_path, &runners_dir, &mut |progress| {
+            report_extract_progress(&window, progress).ok();
+        })
+    })
+    .await
+    .map_err(|e| CustomError::Anyhow(anyhow!("Task join error: {}", e)))??;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn remove_wine_version(name: String, app: AppHandle) -> Result<u64, CustomError> {
+    let app_dir = app.path().resolve("sbrw-utility", BaseDirectory::Data)?;
     let runners_dir = app_dir.join("runners");
-    let file_name = selected_path
-        .file_name()
-        .ok_or_else(|| CustomError::PathError("Invalid archive path".to_string()))?
-        .to_string_lossy()
-        .into_owned();
 
-    let is_wine_archive = file_name.starts_with("wine-");
-    let is_proton_archive = file_name.starts_with("Proton-") || file_name.starts_with("GE-Proton");
+    let runner_dir = find_version_dir(
+        &[runners_dir.join("wine"), runners_dir.join("proton")],
+        &name,
+    )?;
+    let configured_path = PathBuf::from(get_config()?.wine.path);
 
-    let has_valid_extension = file_name.ends_with(".tar.xz") || file_name.ends_with(".tar.gz");
+    tauri::async_runtime::spawn_blocking(move || {
+        remove_version_dir(&name, &runner_dir, &configured_path)
+    })
+    .await
+    .map_err(|e| CustomError::Anyhow(anyhow!("Task join error: {}", e)))?
+}
 
-    if !(is_wine_archive || is_proton_archive) || !has_valid_extension {
-        info!("extraction exited");
+#[tauri::command]
+pub async fn remove_dxvk_version(name: String, app: AppHandle) -> Result<u64, CustomError> {
+    let app_dir = app.path().resolve("sbrw-utility", BaseDirectory::Data)?;
+
+    let dxvk_dir = find_version_dir(&[app_dir.join("runtime/dxvk")], &name)?;
+    let configured_path = PathBuf::from(get_config()?.dxvk.path);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        remove_version_dir(&name, &dxvk_dir, &configured_path)
+    })
+    .await
+    .map_err(|e| CustomError::Anyhow(anyhow!("Task join error: {}", e)))?
+}
+
+fn find_version_dir(parent_dirs: &[PathBuf], name: &str) -> Result<PathBuf, CustomError> {
+    //names come from the frontend, only a plain folder name may reach remove_dir_all
+    let mut components = Path::new(name).components();
+    if !matches!(
+        (components.next(), components.next()),
+        (Some(Component::Normal(_)), None)
+    ) {
         return Err(CustomError::PathError(format!(
-            "Invalid archive file: {}",
-            file_name
+            "Invalid version name: {}",
+            name
         )));
     }
 
-    let base_name = file_name
-        .trim_end_matches(".tar.xz")
-        .trim_end_matches(".tar.gz");
+    parent_dirs
+        .iter()
+        .map(|dir| dir.join(name))
+        .find(|dir| dir.is_dir())
+        .ok_or_else(|| CustomError::PathError(format!("Version {} not found", name)))
+}
 
-    let target_dir = if is_wine_archive {
-        runners_dir.join("wine").join(base_name)
-    } else {
-        runners_dir.join("proton").join(base_name)
-    };
+fn remove_version_dir(
+    name: &str,
+    version_dir: &Path,
+    configured_path: &Path,
+) -> Result<u64, CustomError> {
+    //config paths point inside the version, e.g. at a runner's bin dir
+    if configured_path.starts_with(version_dir) {
+        return Err(CustomError::InUse {
+            name: name.to_string(),
+            user: "the current settings".to_string(),
+        });
+    }
 
-    if target_dir.exists() {
-        return Err(CustomError::PathError(format!(
-            "Version already exists at {}",
-            target_dir.display()
-        )));
+    if is_path_in_use(version_dir) {
+        return Err(CustomError::InUse {
+            name: name.to_string(),
+            user: "a running game".to_string(),
+        });
     }
-    fs::create_dir_all(&target_dir)?;
 
-    let selected_path_ = selected_path.to_owned();
-    let target_dir_ = target_dir.clone();
-    tauri::async_runtime::spawn_blocking(move || extract_archive(&selected_path_, &target_dir_))
-        .await
-        .map_err(|e| CustomError::Anyhow(anyhow!("Task join error: {}", e)))??;
+    let reclaimed_bytes = dir_size(version_dir)?;
+    fs::remove_dir_all(version_dir)?;
 
-    info!("Added version from archive: {}", file_name);
+    info!(
+        "Removed {}, reclaimed {} bytes",
+        version_dir.display(),
+        reclaimed_bytes
+    );
 
-    Ok(())
+    Ok(reclaimed_bytes)
 }
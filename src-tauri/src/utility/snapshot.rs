@@ -0,0 +1,87 @@
+use std::path::Path;
+
+use anyhow::anyhow;
+use log::warn;
+use tauri::{path::BaseDirectory, AppHandle, Manager, Window};
+
+use crate::{
+    setup::wine::release_prefix,
+    types::{error::CustomError, snapshot::PrefixSnapshot},
+    utils::{
+        emitter::report_extract_progress,
+        initialize::get_config,
+        snapshot::{create_snapshot, delete_snapshot, list_snapshots, restore_snapshot},
+    },
+};
+
+#[tauri::command]
+pub async fn create_prefix_snapshot(
+    include_game_files: bool,
+    app: AppHandle,
+) -> Result<PrefixSnapshot, CustomError> {
+    let snapshots_dir = app
+        .path()
+        .resolve("sbrw-utility/snapshots", BaseDirectory::Data)?;
+    let config = get_config()?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        release_prefix(&config.game.prefix, Path::new(&config.wine.path))?;
+        create_snapshot(&snapshots_dir, &config, include_game_files)
+    })
+    .await
+    .map_err(|e| CustomError::Anyhow(anyhow!("Task join error: {}", e)))?
+}
+
+#[tauri::command]
+pub fn list_prefix_snapshots(app: AppHandle) -> Result<Vec<PrefixSnapshot>, CustomError> {
+    let snapshots_dir = app
+        .path()
+        .resolve("sbrw-utility/snapshots", BaseDirectory::Data)?;
+
+    list_snapshots(&snapshots_dir)
+}
+
+//only the prefix goes back, the runner and dxvk picked in the settings stay as they are
+#[tauri::command]
+pub async fn restore_prefix_snapshot(
+    id: String,
+    window: Window,
+) -> Result<PrefixSnapshot, CustomError> {
+    let snapshots_dir = window
+        .path()
+        .resolve("sbrw-utility/snapshots", BaseDirectory::Data)?;
+    let config = get_config()?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        release_prefix(&config.game.prefix, Path::new(&config.wine.path))?;
+
+        let snapshot = restore_snapshot(
+            &snapshots_dir,
+            &id,
+            Path::new(&config.game.prefix),
+            &mut |progress| {
+                report_extract_progress(&window, progress).ok();
+            },
+        )?;
+
+        if snapshot.runner != config.wine.version {
+            warn!(
+                "Snapshot {} was taken with {}, the prefix now runs with {}",
+                snapshot.id, snapshot.runner, config.wine.version
+            );
+        }
+
+        Ok(snapshot)
+    })
+    .await
+    .map_err(|e| CustomError::Anyhow(anyhow!("Task join error: {}", e)))?
+}
+
+#[tauri::command]
+pub fn delete_prefix_snapshot(id: String, app: AppHandle) -> Result<(), CustomError> {
+    let snapshots_dir = app
+        .path()
+        .resolve("sbrw-utility/snapshots", BaseDirectory::Data)?;
+
+    delete_snapshot(&snapshots_dir, &id)
+}
@@ -1,17 +1,26 @@
 use std::path::Path;
 
-use log::warn;
+use log::{info, warn};
+use tauri::Window;
 use which::which;
 
 use crate::{
     setup::{
         check_vc_runtimes,
         validate::{
-            check_dxvk_symlinks, check_game_launcher_exe, check_wine_bin_dir, check_wine_prefix,
+            check_dll_overrides, check_dxvk_symlinks, check_game_launcher_exe, check_nine,
+            check_wine_bin_dir, check_wine_prefix,
         },
     },
-    types::error::{CustomError, ValidationError},
+    types::{
+        config::D3D9Backend,
+        error::{CustomError, ValidationError},
+    },
     utility::repair::attempt_repair,
+    utils::{
+        initialize::{get_d3d9_settings, get_dxvk_install_mode, get_persisted_dll_overrides},
+        runner::resolve_proton_root,
+    },
 };
 
 pub fn validate_on_startup(
@@ -19,6 +28,8 @@ pub fn validate_on_startup(
     wine_path: &Path,
     dxvk_path: &Path,
     dxvk_version: &str,
+    dxvk_enabled: bool,
+    window: &Window,
 ) -> Result<(), CustomError> {
     let mut errors: Vec<ValidationError> = Vec::new();
 
@@ -41,12 +52,41 @@ pub fn validate_on_startup(
         // errors.push(ValidationError::MissingWineBinDir);
     }
 
-    //check dxvk symlinks
-    if let Err(_) = check_dxvk_symlinks(&dxvk_path, &wine_prefix_path) {
+    //proton replaces the d3d dlls with its own on every launch, nothing of ours to check
+    let proton_runner = resolve_proton_root(wine_path).is_some();
+    let dxvk_enabled = dxvk_enabled && !proton_runner;
+
+    //check dxvk symlinks, only when dxvk is meant to be in the prefix
+    if proton_runner {
+        info!("Validation: proton runner manages the d3d dlls, skipping dxvk and nine checks");
+    } else if !dxvk_enabled {
+        info!("Validation: dxvk is disabled, skipping symlinks check");
+    } else if let Err(_) = check_dxvk_symlinks(
+        &dxvk_path,
+        &wine_prefix_path,
+        get_dxvk_install_mode(),
+        get_d3d9_settings().backend,
+    ) {
         warn!("Validation Error: dxvk symlinks are missing, attempting repair");
         errors.push(ValidationError::MissingDXVKSymlinks);
     }
 
+    //check gallium nine when it serves d3d9
+    if !proton_runner
+        && get_d3d9_settings().backend == D3D9Backend::Nine
+        && check_nine(wine_prefix_path).is_err()
+    {
+        warn!("Validation Error: Gallium Nine is missing, attempting repair");
+        errors.push(ValidationError::MissingGalliumNine);
+    }
+
+    //check dll overrides kept in the prefix registry, winecfg or installers may have changed them
+    let dll_overrides = get_persisted_dll_overrides();
+    if !dll_overrides.is_empty() && check_dll_overrides(wine_prefix_path, &dll_overrides).is_err() {
+        warn!("Validation Error: dll overrides in the registry drifted, attempting repair");
+        errors.push(ValidationError::DllOverridesDrift);
+    }
+
     //check vc runtimes
     if let Err(_) = check_vc_runtimes(&wine_prefix_path) {
         warn!("Validation Error: VC runtimes are missing");
@@ -54,7 +94,15 @@ pub fn validate_on_startup(
     }
 
     if !errors.is_empty() {
-        attempt_repair(errors, wine_prefix, wine_path, dxvk_path, dxvk_version)?;
+        attempt_repair(
+            errors,
+            wine_prefix,
+            wine_path,
+            dxvk_path,
+            dxvk_version,
+            dxvk_enabled,
+            window,
+        )?;
     }
 
     Ok(())
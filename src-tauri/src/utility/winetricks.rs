@@ -0,0 +1,71 @@
+use std::path::PathBuf;
+
+use anyhow::anyhow;
+use log::info;
+use tauri::Window;
+
+use crate::{
+    setup::wine::release_prefix,
+    types::{error::CustomError, winetricks::WinetricksVerb},
+    utils::{
+        cancel::{cancel_winetricks_token, new_winetricks_token},
+        initialize::{command_is_available, get_config},
+        winetricks::{list_winetricks_verbs, read_installed_verbs, run_winetricks},
+    },
+};
+
+fn ensure_winetricks() -> Result<(), CustomError> {
+    if !command_is_available("winetricks")? {
+        return Err(CustomError::PathError(
+            "winetricks not found, install it from your distro's packages".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_winetricks_verbs() -> Result<Vec<WinetricksVerb>, CustomError> {
+    ensure_winetricks()?;
+
+    let config = get_config()?;
+    let wine_prefix = PathBuf::from(config.game.prefix);
+    let wine_dir = PathBuf::from(config.wine.path);
+
+    tauri::async_runtime::spawn_blocking(move || list_winetricks_verbs(&wine_prefix, &wine_dir))
+        .await
+        .map_err(|e| CustomError::Anyhow(anyhow!("Task join error: {}", e)))?
+}
+
+//returns every verb installed in the prefix afterwards
+#[tauri::command]
+pub async fn install_winetricks_verbs(
+    verbs: Vec<String>,
+    window: Window,
+) -> Result<Vec<String>, CustomError> {
+    ensure_winetricks()?;
+
+    let config = get_config()?;
+    let token = new_winetricks_token()?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        //installers must not run against a prefix the game is using
+        release_prefix(&config.game.prefix, &PathBuf::from(&config.wine.path))?;
+
+        let wine_prefix = PathBuf::from(config.game.prefix);
+        let wine_dir = PathBuf::from(config.wine.path);
+        run_winetricks(&verbs, &wine_prefix, &wine_dir, &window, &token)?;
+        Ok(read_installed_verbs(&wine_prefix))
+    })
+    .await
+    .map_err(|e| CustomError::Anyhow(anyhow!("Task join error: {}", e)))?
+}
+
+#[tauri::command]
+pub fn cancel_winetricks() -> Result<(), CustomError> {
+    info!("Winetricks cancellation requested");
+
+    cancel_winetricks_token()?;
+
+    Ok(())
+}
@@ -0,0 +1,909 @@
+use std::{
+    cell::Cell,
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Read, Write},
+    os::unix::fs::{self as unix_fs, PermissionsExt},
+    path::{Component, Path, PathBuf},
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use bzip2::read::BzDecoder;
+use flate2::read::GzDecoder;
+use log::info;
+use lz4_flex::frame::FrameDecoder;
+use tar::EntryType;
+use xz2::read::XzDecoder;
+use zip::ZipArchive;
+use zstd::stream::{read::Decoder as ZstdDecoder, write::Encoder as ZstdEncoder};
+
+use crate::types::{
+    archive::{ExtractOptions, ExtractProgress},
+    error::CustomError,
+};
+
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+//zstd's own default, fast enough for a prefix with the game files in it
+const ZSTD_LEVEL: i32 = 3;
+
+//magic bytes at the start of each supported format
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const XZ_MAGIC: &[u8] = &[0xfd, b'7', b'z', b'X', b'Z', 0x00];
+const BZIP2_MAGIC: &[u8] = b"BZh";
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+const LZ4_MAGIC: &[u8] = &[0x04, 0x22, 0x4d, 0x18];
+const ZIP_MAGICS: [&[u8]; 3] = [b"PK\x03\x04", b"PK\x05\x06", b"PK\x07\x08"];
+//plain tarballs have no leading magic, the ustar marker sits in the first header
+const TAR_MAGIC_OFFSET: usize = 257;
+const TAR_MAGIC: &[u8] = b"ustar";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArchiveKind {
+    Tar,
+    TarGz,
+    TarXz,
+    TarBz2,
+    TarZst,
+    TarLz4,
+    Zip,
+}
+
+pub fn detect_archive_kind(file: &Path) -> Result<ArchiveKind, CustomError> {
+    let mut header = Vec::with_capacity(TAR_MAGIC_OFFSET + TAR_MAGIC.len());
+    File::open(file)?
+        .take((TAR_MAGIC_OFFSET + TAR_MAGIC.len()) as u64)
+        .read_to_end(&mut header)?;
+
+    let kind = if header.starts_with(GZIP_MAGIC) {
+        ArchiveKind::TarGz
+    } else if header.starts_with(XZ_MAGIC) {
+        ArchiveKind::TarXz
+    } else if header.starts_with(BZIP2_MAGIC) {
+        ArchiveKind::TarBz2
+    } else if header.starts_with(ZSTD_MAGIC) {
+        ArchiveKind::TarZst
+    } else if header.starts_with(LZ4_MAGIC) {
+        ArchiveKind::TarLz4
+    } else if ZIP_MAGICS.iter().any(|magic| header.starts_with(magic)) {
+        ArchiveKind::Zip
+    } else if header.get(TAR_MAGIC_OFFSET..) == Some(TAR_MAGIC) {
+        ArchiveKind::Tar
+    } else {
+        return Err(CustomError::UnsupportedArchive(
+            file.file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| file.display().to_string()),
+        ));
+    };
+
+    Ok(kind)
+}
+
+//drops the archive extension from a file name, used to name the extracted folder
+pub fn archive_base_name(file_name: &str) -> &str {
+    const EXTENSIONS: [&str; 13] = [
+        ".pkg.tar.zst",
+        ".pkg.tar.xz",
+        ".tar.gz",
+        ".tar.xz",
+        ".tar.bz2",
+        ".tar.zst",
+        ".tar.lz4",
+        ".tgz",
+        ".txz",
+        ".tbz2",
+        ".tzst",
+        ".tar",
+        ".zip",
+    ];
+
+    let lower = file_name.to_lowercase();
+
+    EXTENSIONS
+        .iter()
+        .find(|extension| lower.ends_with(*extension))
+        .map(|extension| &file_name[..file_name.len() - extension.len()])
+        .unwrap_or(file_name)
+}
+
+//reader wrapper that counts how much of the archive file has been consumed
+struct CountingReader<R> {
+    inner: R,
+    count: Rc<Cell<u64>>,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read_bytes = self.inner.read(buf)?;
+        self.count.set(self.count.get() + read_bytes as u64);
+        Ok(read_bytes)
+    }
+}
+
+struct ProgressReporter<'a> {
+    file_name: String,
+    total_bytes: u64,
+    entries_extracted: u64,
+    last_report: Option<Instant>,
+    on_progress: &'a mut dyn FnMut(ExtractProgress),
+}
+
+impl ProgressReporter<'_> {
+    fn entry_done(&mut self, entry: &Path, processed_bytes: u64) {
+        self.entries_extracted += 1;
+
+        //every entry is counted, but events are throttled so big runners don't flood the ui
+        if self
+            .last_report
+            .is_some_and(|last| last.elapsed() < PROGRESS_INTERVAL)
+        {
+            return;
+        }
+
+        self.last_report = Some(Instant::now());
+        self.report(entry, processed_bytes);
+    }
+
+    fn finish(&mut self) {
+        self.report(Path::new(""), self.total_bytes);
+    }
+
+    fn report(&mut self, entry: &Path, processed_bytes: u64) {
+        (self.on_progress)(ExtractProgress {
+            file_name: self.file_name.clone(),
+            entry: entry.to_string_lossy().into_owned(),
+            entries_extracted: self.entries_extracted,
+            processed_bytes,
+            total_bytes: self.total_bytes,
+        });
+    }
+}
+
+pub fn extract_archive(
+    file: &Path,
+    dest: &Path,
+    options: &ExtractOptions,
+    on_progress: &mut dyn FnMut(ExtractProgress),
+) -> Result<(), CustomError> {
+    let file_name = file
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .ok_or_else(|| CustomError::PathError(format!("Invalid archive path: {:?}", file)))?;
+
+    let kind = detect_archive_kind(file)?;
+
+    fs::create_dir_all(dest)?;
+    let dest = dest.canonicalize()?;
+
+    let archive_file = File::open(file)?;
+    let mut reporter = ProgressReporter {
+        file_name: file_name.clone(),
+        total_bytes: archive_file.metadata()?.len(),
+        entries_extracted: 0,
+        last_report: None,
+        on_progress,
+    };
+
+    info!("Extracting {} to {}", file_name, dest.display());
+
+    let count = Rc::new(Cell::new(0));
+    let reader = CountingReader {
+        inner: BufReader::new(archive_file),
+        count: count.clone(),
+    };
+
+    if kind == ArchiveKind::Zip {
+        extract_zip(file, &dest, options, &mut reporter)?;
+    } else {
+        extract_tar(
+            tar_decoder(kind, reader)?,
+            &count,
+            &dest,
+            options,
+            &mut reporter,
+        )?;
+    }
+
+    reporter.finish();
+
+    info!(
+        "Extracted {} entries from {}",
+        reporter.entries_extracted, file_name
+    );
+
+    Ok(())
+}
+
+//packs a directory into a .tar.zst, returns the number of entries written
+//links are stored as links, the prefix points into runner and dxvk folders that shouldn't be copied
+pub fn create_tar_zst(source: &Path, file: &Path, excluded: &[&Path]) -> Result<u64, CustomError> {
+    let encoder = ZstdEncoder::new(BufWriter::new(File::create(file)?), ZSTD_LEVEL)?;
+    let mut builder = tar::Builder::new(encoder);
+    builder.follow_symlinks(false);
+
+    info!("Archiving {} to {}", source.display(), file.display());
+
+    let entries = append_tree(&mut builder, source, Path::new(""), excluded)?;
+
+    builder.into_inner()?.finish()?.flush()?;
+
+    info!("Archived {} entries from {}", entries, source.display());
+
+    Ok(entries)
+}
+
+fn append_tree<W: Write>(
+    builder: &mut tar::Builder<W>,
+    dir: &Path,
+    relative_dir: &Path,
+    excluded: &[&Path],
+) -> Result<u64, CustomError> {
+    let mut dir_entries = fs::read_dir(dir)?.collect::<Result<Vec<_>, _>>()?;
+    dir_entries.sort_by_key(|entry| entry.file_name());
+
+    let mut entries = 0;
+
+    for dir_entry in dir_entries {
+        let path = dir_entry.path();
+        let relative_path = relative_dir.join(dir_entry.file_name());
+        if excluded.contains(&relative_path.as_path()) {
+            continue;
+        }
+
+        let file_type = dir_entry.file_type()?;
+        if file_type.is_dir() {
+            builder.append_dir(&relative_path, &path)?;
+            entries += 1 + append_tree(builder, &path, &relative_path, excluded)?;
+        } else if file_type.is_file() || file_type.is_symlink() {
+            builder.append_path_with_name(&path, &relative_path)?;
+            entries += 1;
+        }
+        //sockets and fifos left behind by wine can't be restored anyway
+    }
+
+    Ok(entries)
+}
+
+//walks every non-directory entry without extracting anything, paths are checked like during extraction
+pub fn scan_archive(
+    file: &Path,
+    visit: &mut dyn FnMut(&Path, &mut dyn Read) -> Result<(), CustomError>,
+) -> Result<(), CustomError> {
+    let kind = detect_archive_kind(file)?;
+
+    if kind == ArchiveKind::Zip {
+        let mut archive = ZipArchive::new(BufReader::new(File::open(file)?))?;
+
+        for index in 0..archive.len() {
+            let mut zip_file = archive.by_index(index)?;
+            if zip_file.is_dir() {
+                continue;
+            }
+
+            let entry_path = zip_file.enclosed_name().ok_or_else(|| {
+                CustomError::UnsafeArchiveEntry(format!(
+                    "path escapes the destination: {}",
+                    zip_file.name()
+                ))
+            })?;
+
+            if let Some(path) = strip_entry_path(&entry_path, &ExtractOptions::default())? {
+                visit(&path, &mut zip_file)?;
+            }
+        }
+
+        return Ok(());
+    }
+
+    let mut archive = tar::Archive::new(tar_decoder(kind, BufReader::new(File::open(file)?))?);
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+
+        if matches!(
+            entry.header().entry_type(),
+            EntryType::Directory | EntryType::XGlobalHeader
+        ) {
+            continue;
+        }
+
+        let entry_path = entry.path()?.into_owned();
+        if let Some(path) = strip_entry_path(&entry_path, &ExtractOptions::default())? {
+            visit(&path, &mut entry)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn tar_decoder<'a, R: Read + 'a>(
+    kind: ArchiveKind,
+    reader: R,
+) -> Result<Box<dyn Read + 'a>, CustomError> {
+    let decoder: Box<dyn Read> = match kind {
+        ArchiveKind::Tar => Box::new(reader),
+        ArchiveKind::TarGz => Box::new(GzDecoder::new(reader)),
+        ArchiveKind::TarXz => Box::new(XzDecoder::new(reader)),
+        ArchiveKind::TarBz2 => Box::new(BzDecoder::new(reader)),
+        ArchiveKind::TarZst => Box::new(ZstdDecoder::new(reader)?),
+        ArchiveKind::TarLz4 => Box::new(FrameDecoder::new(reader)),
+        ArchiveKind::Zip => {
+            return Err(CustomError::UnsupportedArchive(
+                "zip is not a tarball".to_string(),
+            ))
+        }
+    };
+
+    Ok(decoder)
+}
+
+fn extract_tar<R: Read>(
+    reader: R,
+    count: &Rc<Cell<u64>>,
+    dest: &Path,
+    options: &ExtractOptions,
+    reporter: &mut ProgressReporter,
+) -> Result<(), CustomError> {
+    let mut archive = tar::Archive::new(reader);
+    //wine binaries need their exec bits
+    archive.set_preserve_permissions(true);
+    archive.set_overwrite(true);
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let entry_type = entry.header().entry_type();
+
+        //pax global headers carry metadata only
+        if entry_type == EntryType::XGlobalHeader {
+            continue;
+        }
+
+        let entry_path = entry.path()?.into_owned();
+
+        let relative_path = match strip_entry_path(&entry_path, options)? {
+            Some(path) => path,
+            None => continue,
+        };
+
+        let target_path = resolve_target(dest, &relative_path)?;
+
+        if entry_type == EntryType::Link {
+            //hard link targets are archive paths, so they go through the same checks
+            let link_name = entry.link_name()?.ok_or_else(|| {
+                CustomError::UnsafeArchiveEntry(format!(
+                    "hard link without a target: {}",
+                    entry_path.display()
+                ))
+            })?;
+
+            let link_relative = strip_entry_path(&link_name, options)?.ok_or_else(|| {
+                CustomError::UnsafeArchiveEntry(format!(
+                    "hard link target is outside the extracted tree: {}",
+                    link_name.display()
+                ))
+            })?;
+
+            let link_source = resolve_target(dest, &link_relative)?;
+
+            remove_existing_file(&target_path)?;
+            fs::hard_link(&link_source, &target_path)?;
+        } else {
+            //a directory entry would otherwise chmod whatever a symlink left here points to
+            remove_existing_file(&target_path)?;
+            entry.unpack(&target_path)?;
+        }
+
+        reporter.entry_done(&relative_path, count.get());
+    }
+
+    Ok(())
+}
+
+fn extract_zip(
+    file: &Path,
+    dest: &Path,
+    options: &ExtractOptions,
+    reporter: &mut ProgressReporter,
+) -> Result<(), CustomError> {
+    let mut archive = ZipArchive::new(BufReader::new(File::open(file)?))?;
+    let mut processed_bytes = 0;
+
+    for index in 0..archive.len() {
+        let mut zip_file = archive.by_index(index)?;
+
+        let entry_path = zip_file.enclosed_name().ok_or_else(|| {
+            CustomError::UnsafeArchiveEntry(format!(
+                "path escapes the destination: {}",
+                zip_file.name()
+            ))
+        })?;
+
+        processed_bytes += zip_file.compressed_size();
+
+        let relative_path = match strip_entry_path(&entry_path, options)? {
+            Some(path) => path,
+            None => continue,
+        };
+
+        let target_path = resolve_target(dest, &relative_path)?;
+
+        if zip_file.is_dir() {
+            remove_existing_file(&target_path)?;
+            fs::create_dir_all(&target_path)?;
+        } else if zip_file.is_symlink() {
+            let mut link_target = String::new();
+            zip_file.read_to_string(&mut link_target)?;
+
+            remove_existing_file(&target_path)?;
+            unix_fs::symlink(link_target, &target_path)?;
+        } else {
+            //never write through a symlink left by an earlier entry
+            remove_existing_file(&target_path)?;
+            let mut out_file = File::create(&target_path)?;
+            io::copy(&mut zip_file, &mut out_file)?;
+
+            if let Some(mode) = zip_file.unix_mode() {
+                fs::set_permissions(&target_path, fs::Permissions::from_mode(mode))?;
+            }
+        }
+
+        reporter.entry_done(&relative_path, processed_bytes);
+    }
+
+    Ok(())
+}
+
+//drops the root or the first strip_components components and rejects anything that is not a plain
+//relative path, returns None for entries that are stripped away entirely or lie outside the root
+fn strip_entry_path(path: &Path, options: &ExtractOptions) -> Result<Option<PathBuf>, CustomError> {
+    let mut normal_components = Vec::new();
+
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => normal_components.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => {
+                return Err(CustomError::UnsafeArchiveEntry(format!(
+                    "path escapes the destination: {}",
+                    path.display()
+                )));
+            }
+        }
+    }
+
+    let strip_components = match &options.root {
+        Some(root) => {
+            let root_components: Vec<_> = root
+                .components()
+                .filter_map(|component| match component {
+                    Component::Normal(part) => Some(part),
+                    _ => None,
+                })
+                .collect();
+
+            if !normal_components.starts_with(&root_components) {
+                return Ok(None);
+            }
+
+            root_components.len()
+        }
+        None => options.strip_components,
+    };
+
+    if normal_components.len() <= strip_components {
+        return Ok(None);
+    }
+
+    Ok(Some(normal_components[strip_components..].iter().collect()))
+}
+
+//walks the parent folders inside dest before creating any of them, an earlier symlink entry
+//could otherwise redirect the writes and the permissions set afterwards out of it
+fn resolve_target(dest: &Path, relative_path: &Path) -> Result<PathBuf, CustomError> {
+    let mut parent = dest.to_path_buf();
+
+    for component in relative_path
+        .parent()
+        .into_iter()
+        .flat_map(Path::components)
+    {
+        parent.push(component);
+
+        match parent.symlink_metadata() {
+            Ok(metadata) if metadata.file_type().is_symlink() => {
+                return Err(CustomError::UnsafeArchiveEntry(format!(
+                    "path goes through a symlink: {}",
+                    relative_path.display()
+                )));
+            }
+            Ok(_) => {}
+            Err(err) if err.kind() == io::ErrorKind::NotFound => fs::create_dir(&parent)?,
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    Ok(dest.join(relative_path))
+}
+
+fn remove_existing_file(path: &Path) -> Result<(), CustomError> {
+    if let Ok(metadata) = path.symlink_metadata() {
+        if !metadata.is_dir() {
+            fs::remove_file(path)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_server::temp_dir;
+
+    //paths are written into the header as they are, tar::Builder refuses the unsafe ones
+    fn append_raw(
+        builder: &mut tar::Builder<Vec<u8>>,
+        path: &str,
+        entry_type: EntryType,
+        link_name: &str,
+        content: &[u8],
+    ) {
+        let mut header = tar::Header::new_gnu();
+        header.as_old_mut().name[..path.len()].copy_from_slice(path.as_bytes());
+        header.as_old_mut().linkname[..link_name.len()].copy_from_slice(link_name.as_bytes());
+        header.set_entry_type(entry_type);
+        header.set_size(content.len() as u64);
+        header.set_mode(if entry_type == EntryType::Directory {
+            0o755
+        } else {
+            0o644
+        });
+        header.set_cksum();
+        builder.append(&header, content).unwrap();
+    }
+
+    fn write_tar(dir: &Path, entries: &[(&str, EntryType, &str, &[u8])]) -> PathBuf {
+        let mut builder = tar::Builder::new(Vec::new());
+        for (path, entry_type, link_name, content) in entries {
+            append_raw(&mut builder, path, *entry_type, link_name, content);
+        }
+
+        let file = dir.join("archive.tar");
+        fs::write(&file, builder.into_inner().unwrap()).unwrap();
+        file
+    }
+
+    fn extract(file: &Path, dest: &Path, options: &ExtractOptions) -> Result<(), CustomError> {
+        extract_archive(file, dest, options, &mut |_| {})
+    }
+
+    fn write_runner_tree(dir: &Path) -> PathBuf {
+        let source = dir.join("source");
+        fs::create_dir_all(source.join("runner/bin")).unwrap();
+        fs::write(source.join("runner/bin/wine"), "wine").unwrap();
+        fs::set_permissions(
+            source.join("runner/bin/wine"),
+            fs::Permissions::from_mode(0o755),
+        )
+        .unwrap();
+        fs::write(source.join("runner/version"), "9.0").unwrap();
+        source
+    }
+
+    fn tarball(source: &Path) -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        builder
+            .append_dir_all("runner", source.join("runner"))
+            .unwrap();
+        builder.into_inner().unwrap()
+    }
+
+    fn compress(kind: ArchiveKind, tar: &[u8], source: &Path, dir: &Path) -> Vec<u8> {
+        match kind {
+            ArchiveKind::Tar => tar.to_vec(),
+            ArchiveKind::TarGz => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(tar).unwrap();
+                encoder.finish().unwrap()
+            }
+            ArchiveKind::TarXz => {
+                let mut encoder = xz2::write::XzEncoder::new(Vec::new(), 6);
+                encoder.write_all(tar).unwrap();
+                encoder.finish().unwrap()
+            }
+            ArchiveKind::TarBz2 => {
+                let mut encoder =
+                    bzip2::write::BzEncoder::new(Vec::new(), bzip2::Compression::default());
+                encoder.write_all(tar).unwrap();
+                encoder.finish().unwrap()
+            }
+            ArchiveKind::TarZst => {
+                let file = dir.join("snapshot.tar.zst");
+                create_tar_zst(source, &file, &[]).unwrap();
+                fs::read(file).unwrap()
+            }
+            ArchiveKind::TarLz4 => {
+                let mut encoder = lz4_flex::frame::FrameEncoder::new(Vec::new());
+                encoder.write_all(tar).unwrap();
+                encoder.finish().unwrap()
+            }
+            ArchiveKind::Zip => {
+                let mut writer = zip::ZipWriter::new(io::Cursor::new(Vec::new()));
+                for (name, mode, content) in [
+                    ("runner/bin/wine", 0o755, "wine"),
+                    ("runner/version", 0o644, "9.0"),
+                ] {
+                    writer
+                        .start_file(
+                            name,
+                            zip::write::SimpleFileOptions::default().unix_permissions(mode),
+                        )
+                        .unwrap();
+                    writer.write_all(content.as_bytes()).unwrap();
+                }
+                writer.finish().unwrap().into_inner()
+            }
+        }
+    }
+
+    #[test]
+    fn detects_and_extracts_every_format_whatever_the_extension() {
+        let dir = temp_dir("archive-formats");
+        let source = write_runner_tree(&dir);
+        let tar = tarball(&source);
+
+        for kind in [
+            ArchiveKind::Tar,
+            ArchiveKind::TarGz,
+            ArchiveKind::TarXz,
+            ArchiveKind::TarBz2,
+            ArchiveKind::TarZst,
+            ArchiveKind::TarLz4,
+            ArchiveKind::Zip,
+        ] {
+            //the content decides, never the name
+            let file_name = if kind == ArchiveKind::Zip {
+                "runner.tar.gz"
+            } else {
+                "runner.zip"
+            };
+            let file = dir.join(format!("{:?}", kind)).join(file_name);
+            fs::create_dir_all(file.parent().unwrap()).unwrap();
+            fs::write(&file, compress(kind, &tar, &source, &dir)).unwrap();
+
+            assert_eq!(detect_archive_kind(&file).unwrap(), kind);
+
+            let dest = dir.join(format!("{:?}-extracted", kind));
+            extract(
+                &file,
+                &dest,
+                &ExtractOptions {
+                    strip_components: 1,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+            assert_eq!(fs::read_to_string(dest.join("version")).unwrap(), "9.0");
+            assert_eq!(fs::read_to_string(dest.join("bin/wine")).unwrap(), "wine");
+            let mode = fs::metadata(dest.join("bin/wine"))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o111, 0o111, "{:?} lost the exec bits", kind);
+        }
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn rejects_files_that_are_no_archive() {
+        let dir = temp_dir("archive-unsupported");
+        let file = dir.join("runner.tar.xz");
+        fs::write(&file, "not an archive").unwrap();
+
+        assert!(matches!(
+            detect_archive_kind(&file),
+            Err(CustomError::UnsupportedArchive(_))
+        ));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn rejects_parent_dir_traversal() {
+        let dir = temp_dir("archive-traversal");
+        let file = write_tar(
+            &dir,
+            &[("../escaped", EntryType::Regular, "", b"outside".as_slice())],
+        );
+
+        let result = extract(&file, &dir.join("dest"), &ExtractOptions::default());
+
+        assert!(matches!(result, Err(CustomError::UnsafeArchiveEntry(_))));
+        assert!(!dir.join("escaped").exists());
+    }
+
+    #[test]
+    fn rejects_absolute_paths() {
+        let dir = temp_dir("archive-absolute");
+        let outside = dir.join("outside");
+        let file = write_tar(
+            &dir,
+            &[(
+                outside.to_str().unwrap(),
+                EntryType::Regular,
+                "",
+                b"outside".as_slice(),
+            )],
+        );
+
+        let result = extract(&file, &dir.join("dest"), &ExtractOptions::default());
+
+        assert!(matches!(result, Err(CustomError::UnsafeArchiveEntry(_))));
+        assert!(!outside.exists());
+    }
+
+    #[test]
+    fn rejects_files_written_through_a_symlink() {
+        let dir = temp_dir("archive-symlink");
+        let outside = dir.join("outside");
+        fs::create_dir_all(&outside).unwrap();
+        let file = write_tar(
+            &dir,
+            &[
+                (
+                    "link",
+                    EntryType::Symlink,
+                    outside.to_str().unwrap(),
+                    b"".as_slice(),
+                ),
+                (
+                    "link/sub/escaped",
+                    EntryType::Regular,
+                    "",
+                    b"outside".as_slice(),
+                ),
+            ],
+        );
+
+        let result = extract(&file, &dir.join("dest"), &ExtractOptions::default());
+
+        assert!(matches!(result, Err(CustomError::UnsafeArchiveEntry(_))));
+        //nothing may be created outside, not even the folders leading to the file
+        assert_eq!(fs::read_dir(&outside).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn replaces_a_symlink_with_a_directory_entry() {
+        let dir = temp_dir("archive-symlink-dir");
+        let outside = dir.join("outside");
+        fs::create_dir_all(&outside).unwrap();
+        fs::set_permissions(&outside, fs::Permissions::from_mode(0o700)).unwrap();
+        let file = write_tar(
+            &dir,
+            &[
+                (
+                    "link",
+                    EntryType::Symlink,
+                    outside.to_str().unwrap(),
+                    b"".as_slice(),
+                ),
+                ("link", EntryType::Directory, "", b"".as_slice()),
+            ],
+        );
+
+        extract(&file, &dir.join("dest"), &ExtractOptions::default()).unwrap();
+
+        assert!(!dir.join("dest/link").is_symlink());
+        assert!(dir.join("dest/link").is_dir());
+        let outside_mode = fs::metadata(&outside).unwrap().permissions().mode();
+        assert_eq!(outside_mode & 0o777, 0o700);
+    }
+
+    #[test]
+    fn rejects_hard_links_pointing_outside() {
+        let dir = temp_dir("archive-hardlink");
+        let outside = dir.join("outside");
+        fs::create_dir_all(&outside).unwrap();
+        fs::write(outside.join("secret"), "secret").unwrap();
+
+        let traversal = write_tar(
+            &dir,
+            &[("copy", EntryType::Link, "../outside/secret", b"".as_slice())],
+        );
+        let result = extract(&traversal, &dir.join("dest"), &ExtractOptions::default());
+        assert!(matches!(result, Err(CustomError::UnsafeArchiveEntry(_))));
+
+        let through_symlink = write_tar(
+            &dir,
+            &[
+                (
+                    "link",
+                    EntryType::Symlink,
+                    outside.to_str().unwrap(),
+                    b"".as_slice(),
+                ),
+                ("copy", EntryType::Link, "link/secret", b"".as_slice()),
+            ],
+        );
+        let result = extract(
+            &through_symlink,
+            &dir.join("dest2"),
+            &ExtractOptions::default(),
+        );
+        assert!(matches!(result, Err(CustomError::UnsafeArchiveEntry(_))));
+        assert!(!dir.join("dest2/copy").exists());
+    }
+
+    #[test]
+    fn strips_components_and_extracts_below_the_root() {
+        let dir = temp_dir("archive-strip");
+        let file = write_tar(
+            &dir,
+            &[
+                (
+                    "runner/bin/wine",
+                    EntryType::Regular,
+                    "",
+                    b"wine".as_slice(),
+                ),
+                ("runner/version", EntryType::Regular, "", b"9.0".as_slice()),
+                (
+                    "runner/bin/wine64",
+                    EntryType::Link,
+                    "runner/bin/wine",
+                    b"".as_slice(),
+                ),
+                (
+                    "extras/readme",
+                    EntryType::Regular,
+                    "",
+                    b"readme".as_slice(),
+                ),
+            ],
+        );
+
+        let stripped = dir.join("stripped");
+        extract(
+            &file,
+            &stripped,
+            &ExtractOptions {
+                strip_components: 1,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            fs::read_to_string(stripped.join("bin/wine")).unwrap(),
+            "wine"
+        );
+        assert_eq!(
+            fs::read_to_string(stripped.join("bin/wine64")).unwrap(),
+            "wine"
+        );
+        assert_eq!(
+            fs::read_to_string(stripped.join("readme")).unwrap(),
+            "readme"
+        );
+
+        let rooted = dir.join("rooted");
+        extract(
+            &file,
+            &rooted,
+            &ExtractOptions {
+                root: Some(PathBuf::from("runner")),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(fs::read_to_string(rooted.join("version")).unwrap(), "9.0");
+        assert_eq!(
+            fs::read_to_string(rooted.join("bin/wine64")).unwrap(),
+            "wine"
+        );
+        assert!(!rooted.join("readme").exists());
+        assert!(!rooted.join("extras").exists());
+    }
+}
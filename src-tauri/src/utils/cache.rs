@@ -0,0 +1,296 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{Mutex, MutexGuard},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::anyhow;
+use log::{info, warn};
+use once_cell::sync::Lazy;
+
+use crate::{
+    types::{
+        cache::{CacheEntry, CacheIndex, CacheInfo},
+        config::CacheComponent,
+        error::CustomError,
+    },
+    utils::{checksum::sha256_file, fs::cleanup_dir, initialize::get_config},
+};
+
+const INDEX_FILE_NAME: &str = "index.json";
+const BLOBS_DIR_NAME: &str = "blobs";
+
+//serializes index reads and writes between setup, repair and the cache commands
+static CACHE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+pub fn lookup_cached_file(
+    cache_dir: &Path,
+    url: &str,
+    expected_checksum: Option<&str>,
+) -> Result<Option<PathBuf>, CustomError> {
+    let _guard = lock_cache()?;
+    let mut index = load_index(cache_dir)?;
+
+    //a known checksum finds the file no matter which url it came from
+    let position = match expected_checksum {
+        Some(checksum) => index
+            .entries
+            .iter()
+            .position(|entry| entry.sha256.eq_ignore_ascii_case(checksum)),
+        None => index.entries.iter().position(|entry| entry.url == url),
+    };
+
+    let entry = match position {
+        Some(position) => &mut index.entries[position],
+        None => return Ok(None),
+    };
+
+    let blob_path = blob_path(cache_dir, &entry.sha256);
+    let blob_size = fs::metadata(&blob_path).map(|m| m.len()).ok();
+
+    //a hit by url alone is checked by nobody else, so every blob has to match its content address
+    let intact = blob_size == Some(entry.size)
+        && sha256_file(&blob_path).is_ok_and(|sha256| sha256.eq_ignore_ascii_case(&entry.sha256));
+
+    if !intact {
+        warn!("Dropping stale or damaged cache entry for {}", entry.url);
+        let sha256 = entry.sha256.clone();
+        index.entries.retain(|entry| entry.sha256 != sha256);
+        let _ = fs::remove_file(&blob_path);
+        save_index(cache_dir, &index)?;
+        return Ok(None);
+    }
+
+    entry.last_used = now_secs();
+    save_index(cache_dir, &index)?;
+
+    info!("Cache hit for {}", url);
+
+    Ok(Some(blob_path))
+}
+
+//for when the release api can't be asked which file is the current one
+pub fn find_newest_cached_file(
+    cache_dir: &Path,
+    matches: impl Fn(&CacheEntry) -> bool,
+) -> Result<Option<(CacheEntry, PathBuf)>, CustomError> {
+    let newest = {
+        let _guard = lock_cache()?;
+        load_index(cache_dir)?
+            .entries
+            .into_iter()
+            .filter(|entry| matches(entry))
+            .max_by_key(|entry| entry.last_used)
+    };
+
+    match newest {
+        Some(entry) => Ok(
+            lookup_cached_file(cache_dir, &entry.url, Some(&entry.sha256))?
+                .map(|blob_path| (entry, blob_path)),
+        ),
+        None => Ok(None),
+    }
+}
+
+pub fn store_cached_file(
+    cache_dir: &Path,
+    url: &str,
+    file_name: &str,
+    file_path: &Path,
+) -> Result<(), CustomError> {
+    let max_size = max_cache_size();
+    let size = fs::metadata(file_path)?.len();
+
+    if size > max_size {
+        info!(
+            "{} is larger than the cache limit, not caching it",
+            file_name
+        );
+        return Ok(());
+    }
+
+    let sha256 = sha256_file(file_path)?;
+
+    let _guard = lock_cache()?;
+    let mut index = load_index(cache_dir)?;
+
+    let blob_path = blob_path(cache_dir, &sha256);
+    if !blob_path.exists() {
+        fs::create_dir_all(cache_dir.join(BLOBS_DIR_NAME))?;
+
+        //copying through a temporary name so a crash never leaves a truncated blob
+        let tmp_path = blob_path.with_extension("tmp");
+        fs::copy(file_path, &tmp_path)?;
+        fs::rename(&tmp_path, &blob_path)?;
+    }
+
+    index.entries.retain(|entry| entry.url != url);
+    index.entries.push(CacheEntry {
+        url: url.to_string(),
+        file_name: file_name.to_string(),
+        sha256,
+        size,
+        last_used: now_secs(),
+    });
+
+    evict_least_recently_used(cache_dir, &mut index, max_size);
+    remove_unreferenced_blobs(cache_dir, &index)?;
+    save_index(cache_dir, &index)?;
+
+    info!("Cached {} ({} bytes)", file_name, size);
+
+    Ok(())
+}
+
+pub fn get_cache_info(cache_dir: &Path) -> Result<CacheInfo, CustomError> {
+    let _guard = lock_cache()?;
+    let index = load_index(cache_dir)?;
+
+    Ok(CacheInfo {
+        path: cache_dir.display().to_string(),
+        total_size: unique_size(&index),
+        max_size: max_cache_size(),
+        entries: index.entries,
+    })
+}
+
+pub fn clear_cache(cache_dir: &Path) -> Result<(), CustomError> {
+    let _guard = lock_cache()?;
+    cleanup_dir(cache_dir, "download cache")?;
+
+    Ok(())
+}
+
+fn evict_least_recently_used(cache_dir: &Path, index: &mut CacheIndex, max_size: u64) {
+    index.entries.sort_by_key(|entry| entry.last_used);
+
+    while unique_size(index) > max_size && !index.entries.is_empty() {
+        let evicted = index.entries.remove(0);
+        info!("Evicting {} from download cache", evicted.file_name);
+
+        if !index.entries.iter().any(|e| e.sha256 == evicted.sha256) {
+            let _ = fs::remove_file(blob_path(cache_dir, &evicted.sha256));
+        }
+    }
+}
+
+fn remove_unreferenced_blobs(cache_dir: &Path, index: &CacheIndex) -> Result<(), CustomError> {
+    let blobs_dir = cache_dir.join(BLOBS_DIR_NAME);
+    if !blobs_dir.is_dir() {
+        return Ok(());
+    }
+
+    for entry in fs::read_dir(&blobs_dir)? {
+        let path = entry?.path();
+        let referenced = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| index.entries.iter().any(|e| e.sha256 == name));
+
+        if !referenced {
+            let _ = fs::remove_file(&path);
+        }
+    }
+
+    Ok(())
+}
+
+fn unique_size(index: &CacheIndex) -> u64 {
+    //the same blob may be referenced by several urls
+    let mut seen: Vec<&str> = Vec::new();
+    let mut total = 0;
+
+    for entry in &index.entries {
+        if !seen.contains(&entry.sha256.as_str()) {
+            seen.push(&entry.sha256);
+            total += entry.size;
+        }
+    }
+
+    total
+}
+
+fn load_index(cache_dir: &Path) -> Result<CacheIndex, CustomError> {
+    let index_path = cache_dir.join(INDEX_FILE_NAME);
+
+    if !index_path.exists() {
+        return Ok(CacheIndex::default());
+    }
+
+    let content = fs::read_to_string(&index_path)?;
+
+    //an unreadable index just empties the cache, blobs get cleaned up on the next store
+    Ok(serde_json::from_str(&content).unwrap_or_else(|err| {
+        warn!("Ignoring unreadable download cache index: {}", err);
+        CacheIndex::default()
+    }))
+}
+
+fn save_index(cache_dir: &Path, index: &CacheIndex) -> Result<(), CustomError> {
+    fs::create_dir_all(cache_dir)?;
+
+    let json_string = serde_json::to_string_pretty(index)?;
+
+    //a crash halfway through the index would orphan every blob, so it's swapped in whole
+    let tmp_path = cache_dir.join(format!("{}.tmp", INDEX_FILE_NAME));
+    fs::write(&tmp_path, json_string)?;
+    fs::rename(&tmp_path, cache_dir.join(INDEX_FILE_NAME))?;
+
+    Ok(())
+}
+
+fn blob_path(cache_dir: &Path, sha256: &str) -> PathBuf {
+    cache_dir.join(BLOBS_DIR_NAME).join(sha256.to_lowercase())
+}
+
+fn max_cache_size() -> u64 {
+    //setup runs before config.json exists, so fall back to the default limit
+    let cache = get_config()
+        .map(|cfg| cfg.cache)
+        .unwrap_or_else(|_| CacheComponent::default());
+
+    cache.max_size_mb * 1024 * 1024
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn lock_cache() -> Result<MutexGuard<'static, ()>, CustomError> {
+    CACHE_LOCK
+        .lock()
+        .map_err(|_| CustomError::Anyhow(anyhow!("Failed to acquire lock on download cache")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_server::temp_dir;
+
+    const URL: &str = "https://example.com/launcher.zip";
+
+    #[test]
+    fn drops_blobs_that_no_longer_match_their_checksum() {
+        let dir = temp_dir("cache-damaged");
+        let cache_dir = dir.join("cache");
+        let file_path = dir.join("launcher.zip");
+        fs::write(&file_path, "launcher").unwrap();
+
+        store_cached_file(&cache_dir, URL, "launcher.zip", &file_path).unwrap();
+        let blob_path = lookup_cached_file(&cache_dir, URL, None).unwrap().unwrap();
+
+        //same size, different content
+        fs::write(&blob_path, "lAUNCHER").unwrap();
+
+        assert_eq!(lookup_cached_file(&cache_dir, URL, None).unwrap(), None);
+        assert!(!blob_path.exists());
+        assert!(get_cache_info(&cache_dir).unwrap().entries.is_empty());
+        assert!(!cache_dir.join(format!("{}.tmp", INDEX_FILE_NAME)).exists());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
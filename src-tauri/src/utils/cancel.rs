@@ -0,0 +1,80 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, RwLock,
+};
+
+use anyhow::anyhow;
+use once_cell::sync::Lazy;
+
+use crate::types::error::CustomError;
+
+static SETUP_TOKEN: Lazy<RwLock<CancellationToken>> =
+    Lazy::new(|| RwLock::new(CancellationToken::new()));
+//winetricks runs get their own token so cancelling the setup doesn't touch them
+static WINETRICKS_TOKEN: Lazy<RwLock<CancellationToken>> =
+    Lazy::new(|| RwLock::new(CancellationToken::new()));
+
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    pub fn check(&self) -> Result<(), CustomError> {
+        if self.is_cancelled() {
+            return Err(CustomError::Cancelled);
+        }
+
+        Ok(())
+    }
+}
+
+fn renew_token(
+    slot: &RwLock<CancellationToken>,
+    name: &str,
+) -> Result<CancellationToken, CustomError> {
+    //every run gets a fresh token so an old cancel doesn't leak into it
+    let mut token = slot.write().map_err(|_| {
+        CustomError::Anyhow(anyhow!("Failed to acquire write lock on {} token", name))
+    })?;
+    *token = CancellationToken::new();
+
+    Ok(token.clone())
+}
+
+fn cancel_token(slot: &RwLock<CancellationToken>, name: &str) -> Result<(), CustomError> {
+    let token = slot.read().map_err(|_| {
+        CustomError::Anyhow(anyhow!("Failed to acquire read lock on {} token", name))
+    })?;
+    token.cancel();
+
+    Ok(())
+}
+
+pub fn new_setup_token() -> Result<CancellationToken, CustomError> {
+    renew_token(&SETUP_TOKEN, "setup")
+}
+
+pub fn cancel_setup_token() -> Result<(), CustomError> {
+    cancel_token(&SETUP_TOKEN, "setup")
+}
+
+pub fn new_winetricks_token() -> Result<CancellationToken, CustomError> {
+    renew_token(&WINETRICKS_TOKEN, "winetricks")
+}
+
+pub fn cancel_winetricks_token() -> Result<(), CustomError> {
+    cancel_token(&WINETRICKS_TOKEN, "winetricks")
+}
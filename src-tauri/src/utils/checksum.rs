@@ -0,0 +1,63 @@
+use std::{fs::File, io, path::Path};
+
+use log::info;
+use sha2::{Digest, Sha256};
+
+use crate::types::error::CustomError;
+
+pub fn sha256_file(path: &Path) -> Result<String, CustomError> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)?;
+
+    Ok(hex::encode(hasher.finalize()))
+}
+
+pub fn sha256_bytes(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+pub fn verify_sha256(path: &Path, expected: &str) -> Result<(), CustomError> {
+    let expected = expected.trim().to_lowercase();
+    let actual = sha256_file(path)?;
+
+    if actual != expected {
+        return Err(CustomError::ChecksumMismatch {
+            file: path.display().to_string(),
+            expected,
+            actual,
+        });
+    }
+
+    info!("Checksum verified for {:?}", path);
+
+    Ok(())
+}
+
+pub fn parse_checksum_file(content: &str, file_name: &str) -> Option<String> {
+    //supports sha256sum output ("<hash>  <name>" or "<hash> *<name>")
+    //as well as files that only hold the bare hash
+    let mut bare_hash = None;
+
+    for line in content.lines() {
+        let mut parts = line.split_whitespace();
+        let hash = match parts.next() {
+            Some(hash) if is_sha256_hex(hash) => hash,
+            _ => continue,
+        };
+
+        match parts.next() {
+            Some(name) if name.trim_start_matches('*') == file_name => {
+                return Some(hash.to_lowercase())
+            }
+            Some(_) => continue,
+            None => bare_hash = bare_hash.or(Some(hash.to_lowercase())),
+        }
+    }
+
+    bare_hash
+}
+
+fn is_sha256_hex(value: &str) -> bool {
+    value.len() == 64 && value.chars().all(|c| c.is_ascii_hexdigit())
+}
@@ -1,67 +1,910 @@
 use anyhow::anyhow;
-use log::info;
-use reqwest::blocking::Client;
-use serde::{Deserialize, Serialize};
-use std::path::{Path, PathBuf};
+use log::{info, warn};
+use reqwest::{
+    blocking::{Client, RequestBuilder, Response},
+    header::{
+        HeaderMap, AUTHORIZATION, CONTENT_RANGE, ETAG, IF_NONE_MATCH, IF_RANGE, LAST_MODIFIED,
+        RANGE, RETRY_AFTER,
+    },
+    Proxy, StatusCode, Url,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    fs::{self, OpenOptions},
+    io::{BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use tauri::Window;
 
-use crate::types::error::CustomError;
+use crate::{
+    types::{config::NetworkComponent, download::DownloadProgress, error::CustomError},
+    utils::{
+        cache::{lookup_cached_file, store_cached_file},
+        cancel::CancellationToken,
+        checksum::{parse_checksum_file, sha256_bytes, verify_sha256},
+        emitter::report_download_progress,
+        fs::cleanup_file,
+    },
+};
+
+const CHUNK_SIZE: usize = 64 * 1024;
+const MAX_RESUME_ATTEMPTS: u32 = 5;
+const CHECKSUM_SUFFIXES: [&str; 2] = [".sha256sum", ".sha256"];
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+const GITHUB_BASE_URL: &str = "https://github.com";
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+const RELEASES_DIR_NAME: &str = "releases";
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Release {
+    pub tag_name: String,
+    #[serde(default)]
+    pub published_at: Option<String>,
+    #[serde(default)]
+    pub prerelease: bool,
+    pub assets: Vec<Asset>,
+}
 
 #[derive(Debug, Deserialize, Serialize)]
-struct Release {
-    tag_name: String,
-    assets: Vec<Asset>,
+pub struct Asset {
+    pub name: String,
+    pub browser_download_url: String,
+    #[serde(default)]
+    pub size: u64,
+    //GitHub publishes "sha256:<hex>" for every release asset
+    #[serde(default)]
+    pub digest: Option<String>,
+}
+
+impl Asset {
+    pub fn sha256(&self) -> Option<String> {
+        self.digest
+            .as_deref()
+            .and_then(|digest| digest.strip_prefix("sha256:"))
+            .map(str::to_lowercase)
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-struct Asset {
-    name: String,
-    browser_download_url: String,
+struct CachedRelease<T> {
+    etag: Option<String>,
+    release: T,
 }
 
-pub fn build_client() -> Result<Client, CustomError> {
-    let client = Client::builder().user_agent("sbrw-utility").build()?;
-    Ok(client)
+pub struct HttpClient {
+    client: Client,
+    network: NetworkComponent,
+    cache_dir: PathBuf,
+}
+
+impl HttpClient {
+    pub fn api_url(&self, path: &str) -> String {
+        format!(
+            "{}/{}",
+            self.network.api_base_url.trim_end_matches('/'),
+            path.trim_start_matches('/')
+        )
+    }
+
+    fn get(&self, url: &str) -> RequestBuilder {
+        let request = self.client.get(url);
+
+        //the token is only ever sent to the configured api, never to mirrors
+        match self.network.github_token.as_deref() {
+            Some(token) if !token.is_empty() && self.is_api_url(url) => {
+                request.header(AUTHORIZATION, format!("Bearer {}", token))
+            }
+            _ => request,
+        }
+    }
+
+    fn is_api_url(&self, url: &str) -> bool {
+        //compared by origin, a plain prefix match would also accept api.github.com.example
+        let (Ok(url), Ok(api)) = (Url::parse(url), Url::parse(&self.network.api_base_url)) else {
+            return false;
+        };
+        let api_path = api.path().trim_end_matches('/');
+
+        url.scheme() == api.scheme()
+            && url.host_str() == api.host_str()
+            && url.port_or_known_default() == api.port_or_known_default()
+            && (url.path() == api_path || url.path().starts_with(&format!("{}/", api_path)))
+    }
+
+    fn send_with_retry(
+        &self,
+        url: &str,
+        configure: impl Fn(RequestBuilder) -> RequestBuilder,
+    ) -> Result<Response, CustomError> {
+        //only used for GETs, so retrying them is always safe
+        let mut attempt = 0;
+
+        loop {
+            let (reason, retry_after) = match configure(self.get(url)).send() {
+                Ok(response) => {
+                    check_rate_limit(url, &response)?;
+
+                    let status = response.status();
+                    let retryable = status.is_server_error()
+                        || status == StatusCode::TOO_MANY_REQUESTS
+                        || status == StatusCode::REQUEST_TIMEOUT;
+
+                    if !retryable || attempt >= self.network.max_retries {
+                        return Ok(response);
+                    }
+
+                    (status.to_string(), retry_after(response.headers()))
+                }
+                Err(err)
+                    if (err.is_connect() || err.is_timeout())
+                        && attempt < self.network.max_retries =>
+                {
+                    (err.to_string(), None)
+                }
+                Err(err) => return Err(err.into()),
+            };
+
+            attempt += 1;
+
+            //exponential backoff, unless the server told us how long to wait
+            let delay = retry_after
+                .unwrap_or_else(|| Duration::from_secs(1 << attempt.min(5)))
+                .min(MAX_BACKOFF);
+
+            warn!(
+                "Request to {} failed ({}), retrying in {}s (attempt {}/{})",
+                url,
+                reason,
+                delay.as_secs(),
+                attempt,
+                self.network.max_retries
+            );
+
+            thread::sleep(delay);
+        }
+    }
+
+    fn candidate_urls(&self, url: &str) -> Vec<String> {
+        //GitHub downloads are tried through the configured mirrors first,
+        //a mirror replaces the https://github.com part of the url
+        let mut urls: Vec<String> = match url.strip_prefix(GITHUB_BASE_URL) {
+            Some(path) => self
+                .network
+                .mirror_urls
+                .iter()
+                .filter(|mirror| !mirror.is_empty())
+                .map(|mirror| format!("{}{}", mirror.trim_end_matches('/'), path))
+                .collect(),
+            None => Vec::new(),
+        };
+
+        urls.push(url.to_string());
+
+        urls
+    }
+}
+
+pub fn build_client(
+    network: &NetworkComponent,
+    cache_dir: &Path,
+) -> Result<HttpClient, CustomError> {
+    //the read timeout applies to every chunk of a download, not the whole transfer
+    let mut builder = Client::builder()
+        .user_agent("sbrw-utility")
+        .connect_timeout(Duration::from_secs(network.connect_timeout_secs))
+        .timeout(Duration::from_secs(network.read_timeout_secs));
+
+    //http(s):// and socks5:// proxies, without one reqwest still honors the *_PROXY env vars
+    if let Some(proxy_url) = network.proxy.as_deref().filter(|p| !p.is_empty()) {
+        builder = builder.proxy(Proxy::all(proxy_url)?);
+    }
+
+    Ok(HttpClient {
+        client: builder.build()?,
+        network: network.clone(),
+        cache_dir: cache_dir.to_path_buf(),
+    })
 }
 
 pub fn download_using_url(
-    client: &Client,
+    client: &HttpClient,
     url: &str,
     dest: &Path,
     file_name: &str,
+    expected_checksum: Option<&str>,
+    window: &Window,
+    token: Option<&CancellationToken>,
 ) -> Result<PathBuf, CustomError> {
-    //Downloads file using url and stores in local destination path
+    download_file(
+        client,
+        url,
+        dest,
+        file_name,
+        expected_checksum,
+        &mut |progress| {
+            report_download_progress(window, progress).ok();
+        },
+        token,
+    )
+}
 
-    let response = client.get(url).send()?;
+pub fn download_file(
+    client: &HttpClient,
+    url: &str,
+    dest: &Path,
+    file_name: &str,
+    expected_checksum: Option<&str>,
+    on_progress: &mut dyn FnMut(DownloadProgress),
+    token: Option<&CancellationToken>,
+) -> Result<PathBuf, CustomError> {
+    //Downloads file using url into a .part file, resuming it with range
+    //requests after a dropped transfer, and moves it into place once complete.
+    //Files already in the download cache are copied from there instead
+
+    let final_path = dest.join(file_name);
+    let part_path = dest.join(format!("{}.part", file_name));
+    let cache_dir = &client.cache_dir;
+
+    if let Some(cached_path) = lookup_cached_file(cache_dir, url, expected_checksum)? {
+        fs::copy(&cached_path, &final_path)?;
+
+        if let Some(checksum) = expected_checksum {
+            verify_sha256(&final_path, checksum)?;
+        }
+
+        let size = fs::metadata(&final_path)?.len();
+        on_progress(DownloadProgress {
+            file_name: file_name.to_string(),
+            downloaded_bytes: size,
+            total_bytes: Some(size),
+            bytes_per_second: 0,
+            eta_seconds: Some(0),
+        });
+
+        info!("Copied {} from download cache", file_name);
+
+        return Ok(final_path);
+    }
 
-    let tmp_path = dest.join(file_name);
-    let mut out = std::fs::File::create(&tmp_path)?;
-    let mut content = std::io::Cursor::new(response.bytes()?);
-    std::io::copy(&mut content, &mut out)?;
+    let mut last_error = None;
+    for candidate_url in client.candidate_urls(url) {
+        match download_to_part(
+            client,
+            &candidate_url,
+            &part_path,
+            file_name,
+            on_progress,
+            token,
+        ) {
+            Ok(()) => {
+                last_error = None;
+                break;
+            }
+            //the part file is kept, a later run resumes it
+            Err(CustomError::Cancelled) => return Err(CustomError::Cancelled),
+            Err(err) => {
+                warn!(
+                    "Download of {} from {} failed: {}",
+                    file_name, candidate_url, err
+                );
+                last_error = Some(err);
+            }
+        }
+    }
+
+    if let Some(err) = last_error {
+        return Err(err);
+    }
+
+    fs::rename(&part_path, &final_path)?;
+    cleanup_file(&validator_path(&part_path))?;
+
+    if let Some(checksum) = expected_checksum {
+        verify_sha256(&final_path, checksum).inspect_err(|_| {
+            cleanup_file(&final_path).ok();
+        })?;
+    }
+
+    info!("Downloaded {} to {:?}", file_name, final_path);
+
+    //a failing cache must never fail the download itself
+    if let Err(err) = store_cached_file(cache_dir, url, file_name, &final_path) {
+        warn!("Failed to cache {}: {}", file_name, err);
+    }
+
+    Ok(final_path)
+}
+
+fn download_to_part(
+    client: &HttpClient,
+    url: &str,
+    part_path: &Path,
+    file_name: &str,
+    on_progress: &mut dyn FnMut(DownloadProgress),
+    token: Option<&CancellationToken>,
+) -> Result<(), CustomError> {
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+
+        match transfer_to_part(client, url, part_path, file_name, on_progress, token) {
+            Ok(TransferOutcome::Complete) => return Ok(()),
+            Ok(TransferOutcome::Interrupted(reason)) if attempt < MAX_RESUME_ATTEMPTS => {
+                warn!(
+                    "Download of {} interrupted ({}), resuming (attempt {}/{})",
+                    file_name, reason, attempt, MAX_RESUME_ATTEMPTS
+                );
+            }
+            Ok(TransferOutcome::Interrupted(reason)) => {
+                return Err(CustomError::Anyhow(anyhow!(
+                    "Download of {} failed after {} attempts: {}",
+                    file_name,
+                    MAX_RESUME_ATTEMPTS,
+                    reason
+                )));
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+enum TransferOutcome {
+    Complete,
+    Interrupted(String),
+}
+
+fn transfer_to_part(
+    client: &HttpClient,
+    url: &str,
+    part_path: &Path,
+    file_name: &str,
+    on_progress: &mut dyn FnMut(DownloadProgress),
+    token: Option<&CancellationToken>,
+) -> Result<TransferOutcome, CustomError> {
+    if let Some(token) = token {
+        token.check()?;
+    }
+
+    let validator_path = validator_path(part_path);
+    let mut existing_bytes = fs::metadata(part_path).map(|m| m.len()).unwrap_or(0);
+    let validator = fs::read_to_string(&validator_path)
+        .ok()
+        .filter(|validator| !validator.is_empty());
+
+    //without a validator there's no telling whether the part file still
+    //belongs to the remote file, so it isn't resumed
+    if existing_bytes > 0 && validator.is_none() {
+        info!("No validator for the partial {}, starting over", file_name);
+        cleanup_file(part_path)?;
+        existing_bytes = 0;
+    }
+
+    //If-Range makes the server send the whole file again once it changed
+    let response = client.send_with_retry(url, |request| match validator.as_deref() {
+        Some(validator) if existing_bytes > 0 => request
+            .header(RANGE, format!("bytes={}-", existing_bytes))
+            .header(IF_RANGE, validator),
+        _ => request,
+    })?;
+
+    //416 means the part file doesn't fit the remote file anymore, start over
+    if response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+        discard_part(part_path)?;
+        return Ok(TransferOutcome::Interrupted(
+            "stale partial download discarded".to_string(),
+        ));
+    }
+
+    let mut response = response.error_for_status()?;
+
+    //servers without range support answer 200 with the whole file
+    let resumed = response.status() == StatusCode::PARTIAL_CONTENT;
+    let total_bytes = if resumed {
+        match parse_content_range(response.headers()) {
+            Some((start, total)) if start == existing_bytes => {
+                total.or(response.content_length().map(|length| length + start))
+            }
+            _ => {
+                discard_part(part_path)?;
+                return Ok(TransferOutcome::Interrupted(
+                    "content range doesn't continue the partial download".to_string(),
+                ));
+            }
+        }
+    } else {
+        save_validator(&validator_path, response.headers())?;
+        response.content_length()
+    };
+    let offset = if resumed { existing_bytes } else { 0 };
+
+    if resumed {
+        info!("Resuming {} from byte {}", file_name, offset);
+    }
+
+    let file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resumed)
+        .truncate(!resumed)
+        .open(part_path)?;
+    let mut out = BufWriter::new(file);
+
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    let mut downloaded_bytes = offset;
+    let started_at = Instant::now();
+    let mut last_report = started_at;
+
+    loop {
+        //what arrived so far stays in the part file for the next run to resume
+        if token.is_some_and(CancellationToken::is_cancelled) {
+            out.flush()?;
+            return Err(CustomError::Cancelled);
+        }
+
+        let read_bytes = match response.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read_bytes) => read_bytes,
+            Err(err) => {
+                out.flush()?;
+                return Ok(TransferOutcome::Interrupted(err.to_string()));
+            }
+        };
+
+        out.write_all(&buffer[..read_bytes])?;
+        downloaded_bytes += read_bytes as u64;
+
+        //throttling events so the frontend isn't flooded on fast connections
+        if last_report.elapsed() >= PROGRESS_INTERVAL {
+            last_report = Instant::now();
+            on_progress(build_progress(
+                file_name,
+                downloaded_bytes,
+                total_bytes,
+                downloaded_bytes - offset,
+                started_at,
+            ));
+        }
+    }
+
+    out.flush()?;
+
+    on_progress(build_progress(
+        file_name,
+        downloaded_bytes,
+        total_bytes,
+        downloaded_bytes - offset,
+        started_at,
+    ));
+
+    //the part file only counts as complete once it matches Content-Length
+    match total_bytes {
+        Some(total) if downloaded_bytes != total => Ok(TransferOutcome::Interrupted(format!(
+            "received {} of {} bytes",
+            downloaded_bytes, total
+        ))),
+        _ => Ok(TransferOutcome::Complete),
+    }
+}
 
-    Ok(tmp_path)
+fn validator_path(part_path: &Path) -> PathBuf {
+    let mut path = part_path.as_os_str().to_owned();
+    path.push(".validator");
+    PathBuf::from(path)
 }
 
-pub fn get_latest_download_url(
-    client: &Client,
+fn save_validator(validator_path: &Path, headers: &HeaderMap) -> Result<(), CustomError> {
+    //weak etags aren't allowed in If-Range, Last-Modified is the fallback
+    let validator = headers
+        .get(ETAG)
+        .and_then(|value| value.to_str().ok())
+        .filter(|etag| !etag.starts_with("W/"))
+        .or_else(|| {
+            headers
+                .get(LAST_MODIFIED)
+                .and_then(|value| value.to_str().ok())
+        });
+
+    match validator {
+        Some(validator) => fs::write(validator_path, validator)?,
+        None => cleanup_file(validator_path)?,
+    }
+
+    Ok(())
+}
+
+fn discard_part(part_path: &Path) -> Result<(), CustomError> {
+    cleanup_file(part_path)?;
+    cleanup_file(&validator_path(part_path))
+}
+
+fn parse_content_range(headers: &HeaderMap) -> Option<(u64, Option<u64>)> {
+    //bytes <start>-<end>/<total or *>
+    let value = headers.get(CONTENT_RANGE)?.to_str().ok()?;
+    let (range, total) = value.strip_prefix("bytes ")?.split_once('/')?;
+    let (start, _) = range.split_once('-')?;
+
+    Some((start.trim().parse().ok()?, total.trim().parse().ok()))
+}
+
+fn build_progress(
+    file_name: &str,
+    downloaded_bytes: u64,
+    total_bytes: Option<u64>,
+    session_bytes: u64,
+    started_at: Instant,
+) -> DownloadProgress {
+    //speed only counts bytes fetched by this transfer, not the resumed part
+    let elapsed_secs = started_at.elapsed().as_secs_f64();
+    let bytes_per_second = if elapsed_secs > 0.0 {
+        (session_bytes as f64 / elapsed_secs) as u64
+    } else {
+        0
+    };
+
+    let eta_seconds = match total_bytes {
+        Some(total) if bytes_per_second > 0 => {
+            Some(total.saturating_sub(downloaded_bytes) / bytes_per_second)
+        }
+        _ => None,
+    };
+
+    DownloadProgress {
+        file_name: file_name.to_string(),
+        downloaded_bytes,
+        total_bytes,
+        bytes_per_second,
+        eta_seconds,
+    }
+}
+
+pub fn fetch_published_checksum(
+    client: &HttpClient,
+    url: &str,
+    file_name: &str,
+) -> Result<Option<String>, CustomError> {
+    //GitHub releases usually publish the checksum as a sibling asset
+    for suffix in CHECKSUM_SUFFIXES {
+        let checksum_url = format!("{}{}", url, suffix);
+
+        //any failure, 404 or otherwise, only means there's no checksum from this
+        //url, callers decide whether the file may be used without one
+        let content = client
+            .candidate_urls(&checksum_url)
+            .iter()
+            .find_map(|candidate_url| {
+                client
+                    .send_with_retry(candidate_url, |r| r)
+                    .and_then(|response| Ok(response.error_for_status()?.text()?))
+                    .inspect_err(|err| {
+                        info!("No checksum at {}: {}", candidate_url, err);
+                    })
+                    .ok()
+            });
+
+        if let Some(checksum) = content.and_then(|c| parse_checksum_file(&c, file_name)) {
+            return Ok(Some(checksum));
+        }
+    }
+
+    warn!("No published checksum found for {}", file_name);
+
+    Ok(None)
+}
+
+pub fn get_latest_asset(
+    client: &HttpClient,
     repo_url: &str,
     starting_name: &str,
-) -> Result<String, CustomError> {
-    let release: Release = client
-        .get(repo_url)
-        .header("User-Agent", "sbrw-utility")
-        .send()?
-        .json()?;
+) -> Result<Asset, CustomError> {
+    let release: Release = fetch_release(client, repo_url)?;
 
     if let Some(asset) = release
         .assets
-        .iter()
+        .into_iter()
         .find(|a| a.name.starts_with(starting_name))
     {
         info!("{:?}", asset.browser_download_url);
-        Ok(asset.browser_download_url.clone())
+        Ok(asset)
     } else {
-        return Err(CustomError::Anyhow(anyhow!(
+        Err(CustomError::Anyhow(anyhow!(
             "Latest release file not found"
-        )));
+        )))
+    }
+}
+
+pub fn fetch_releases(
+    client: &HttpClient,
+    repo: &str,
+    per_page: u32,
+) -> Result<Vec<Release>, CustomError> {
+    let url = client.api_url(&format!("repos/{}/releases?per_page={}", repo, per_page));
+
+    fetch_release(client, &url)
+}
+
+fn fetch_release<T: Serialize + DeserializeOwned>(
+    client: &HttpClient,
+    repo_url: &str,
+) -> Result<T, CustomError> {
+    //release metadata is kept with its ETag, a 304 answer doesn't count
+    //against GitHub's rate limit and the copy still works when offline
+    let cache_path = release_cache_path(&client.cache_dir, repo_url);
+    let cached = fs::read_to_string(&cache_path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<CachedRelease<T>>(&content).ok());
+    let cached_etag = cached.as_ref().and_then(|c| c.etag.clone());
+
+    let result = client.send_with_retry(repo_url, |request| match &cached_etag {
+        Some(etag) => request.header(IF_NONE_MATCH, etag),
+        None => request,
+    });
+
+    let response = match (result, cached) {
+        (Ok(response), Some(cached)) if response.status() == StatusCode::NOT_MODIFIED => {
+            info!("Release metadata for {} not modified", repo_url);
+            return Ok(cached.release);
+        }
+        (Ok(response), _) => response,
+        (Err(err), Some(cached)) => {
+            warn!("Using cached release metadata for {}: {}", repo_url, err);
+            return Ok(cached.release);
+        }
+        (Err(err), None) => return Err(err),
+    };
+
+    let response = response.error_for_status()?;
+    let etag = response
+        .headers()
+        .get(ETAG)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string());
+    let release: T = response.json()?;
+
+    let cached = CachedRelease { etag, release };
+    if let Err(err) = save_cached_release(&cache_path, &cached) {
+        warn!("Failed to cache release metadata: {}", err);
+    }
+
+    Ok(cached.release)
+}
+
+fn release_cache_path(cache_dir: &Path, repo_url: &str) -> PathBuf {
+    cache_dir
+        .join(RELEASES_DIR_NAME)
+        .join(format!("{}.json", sha256_bytes(repo_url.as_bytes())))
+}
+
+fn save_cached_release<T: Serialize>(
+    cache_path: &Path,
+    cached: &CachedRelease<T>,
+) -> Result<(), CustomError> {
+    if let Some(parent) = cache_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::write(cache_path, serde_json::to_string_pretty(cached)?)?;
+
+    Ok(())
+}
+
+fn check_rate_limit(url: &str, response: &Response) -> Result<(), CustomError> {
+    let headers = response.headers();
+    let remaining = header_u64(headers, "x-ratelimit-remaining");
+
+    if let Some(remaining) = remaining.filter(|remaining| *remaining < 10) {
+        warn!("GitHub API requests left before rate limit: {}", remaining);
+    }
+
+    //GitHub answers 403 or 429 with no requests left when the limit is hit
+    let status = response.status();
+    let limited = (status == StatusCode::FORBIDDEN || status == StatusCode::TOO_MANY_REQUESTS)
+        && remaining == Some(0);
+
+    if !limited {
+        return Ok(());
+    }
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let reset_in_secs = header_u64(headers, "x-ratelimit-reset")
+        .map(|reset| reset.saturating_sub(now))
+        .unwrap_or(0);
+
+    warn!("Rate limited by {}, resets in {}s", url, reset_in_secs);
+
+    Err(CustomError::RateLimited {
+        reset_in_minutes: reset_in_secs.div_ceil(60),
+    })
+}
+
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    header_u64(headers, RETRY_AFTER.as_str()).map(Duration::from_secs)
+}
+
+fn header_u64(headers: &HeaderMap, name: &str) -> Option<u64> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_server::{header, start_server, temp_dir};
+
+    const ETAG_V1: &str = "\"v1\"";
+
+    fn remote_file() -> Vec<u8> {
+        (0..4096).map(|i| (i % 251) as u8).collect()
+    }
+
+    //answers like a static file server: ranges are honored while If-Range matches
+    fn serve_file(request: &str, etag: &str, body: &[u8]) -> Vec<u8> {
+        let range_start = header(request, "range").and_then(|range| {
+            range
+                .strip_prefix("bytes=")?
+                .strip_suffix('-')?
+                .parse()
+                .ok()
+        });
+        let if_range_matches = header(request, "if-range").is_none_or(|value| value == etag);
+
+        match range_start {
+            Some(start) if if_range_matches => partial_response(etag, body, start, start),
+            _ => {
+                let mut response = format!(
+                    "HTTP/1.1 200 OK\r\nETag: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    etag,
+                    body.len()
+                )
+                .into_bytes();
+                response.extend_from_slice(body);
+                response
+            }
+        }
+    }
+
+    fn partial_response(etag: &str, body: &[u8], claimed_start: usize, start: usize) -> Vec<u8> {
+        let mut response = format!(
+            "HTTP/1.1 206 Partial Content\r\nETag: {}\r\nContent-Range: bytes {}-{}/{}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            etag,
+            claimed_start,
+            body.len() - 1,
+            body.len(),
+            body.len() - start
+        )
+        .into_bytes();
+        response.extend_from_slice(&body[start..]);
+        response
+    }
+
+    fn download(name: &str, base_url: &str, part: Option<(&[u8], Option<&str>)>) -> Vec<u8> {
+        let dir = temp_dir(name);
+        let part_path = dir.join("file.bin.part");
+        if let Some((bytes, validator)) = part {
+            fs::write(&part_path, bytes).unwrap();
+            if let Some(validator) = validator {
+                fs::write(validator_path(&part_path), validator).unwrap();
+            }
+        }
+
+        let client = build_client(&NetworkComponent::default(), &dir).unwrap();
+        let url = format!("{}/file.bin", base_url);
+        download_to_part(&client, &url, &part_path, "file.bin", &mut |_| {}, None).unwrap();
+
+        let downloaded = fs::read(&part_path).unwrap();
+        fs::remove_dir_all(&dir).ok();
+        downloaded
+    }
+
+    #[test]
+    fn sends_the_token_only_to_the_api_origin() {
+        let network = NetworkComponent {
+            github_token: Some("secret".to_string()),
+            ..NetworkComponent::default()
+        };
+        let client = build_client(&network, &std::env::temp_dir()).unwrap();
+        let sends_token = |url: &str| {
+            let request = client.get(url).build().unwrap();
+            request
+                .headers()
+                .get(AUTHORIZATION)
+                .is_some_and(|value| value == "Bearer secret")
+        };
+
+        assert!(sends_token("https://api.github.com/repos/a/b/releases"));
+        assert!(sends_token("https://api.github.com:443/repos/a/b/releases"));
+        assert!(!sends_token("https://api.github.com.example/repos/a/b"));
+        assert!(!sends_token("https://api.github.com@example.com/repos/a/b"));
+        assert!(!sends_token("http://api.github.com/repos/a/b"));
+        assert!(!sends_token("https://api.github.com:8443/repos/a/b"));
+        assert!(!sends_token(
+            "https://github.com/a/b/releases/download/v1/file.zip"
+        ));
+    }
+
+    #[test]
+    fn resumes_a_dropped_transfer_with_if_range() {
+        let body = remote_file();
+        let remote = body.clone();
+        let (base_url, requests) = start_server(move |index, request| {
+            let response = serve_file(request, ETAG_V1, &remote);
+            //the first transfer drops halfway through the body
+            match index {
+                0 => response[..response.len() - remote.len() / 2].to_vec(),
+                _ => response,
+            }
+        });
+
+        assert_eq!(download("resume", &base_url, None), body);
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(header(&requests[0], "range"), None);
+        assert_eq!(header(&requests[1], "range"), Some("bytes=2048-"));
+        assert_eq!(header(&requests[1], "if-range"), Some(ETAG_V1));
+    }
+
+    #[test]
+    fn restarts_when_the_remote_file_changed() {
+        let body = remote_file();
+        let remote = body.clone();
+        let (base_url, requests) =
+            start_server(move |_, request| serve_file(request, ETAG_V1, &remote));
+
+        let stale = vec![0xff; 2048];
+        assert_eq!(
+            download("changed", &base_url, Some((&stale, Some("\"v0\"")))),
+            body
+        );
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(header(&requests[0], "if-range"), Some("\"v0\""));
+    }
+
+    #[test]
+    fn restarts_a_part_without_validator() {
+        let body = remote_file();
+        let remote = body.clone();
+        let (base_url, requests) =
+            start_server(move |_, request| serve_file(request, ETAG_V1, &remote));
+
+        let stale = vec![0xff; 2048];
+        assert_eq!(
+            download("unvalidated", &base_url, Some((&stale, None))),
+            body
+        );
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(header(&requests[0], "range"), None);
+    }
+
+    #[test]
+    fn restarts_when_the_content_range_does_not_continue_the_part() {
+        let body = remote_file();
+        let remote = body.clone();
+        let (base_url, requests) = start_server(move |index, request| match index {
+            //claims to start at byte 0 instead of where the part ends
+            0 => partial_response(ETAG_V1, &remote, 0, 2048),
+            _ => serve_file(request, ETAG_V1, &remote),
+        });
+
+        let part = body[..2048].to_vec();
+        assert_eq!(
+            download("mismatch", &base_url, Some((&part, Some(ETAG_V1)))),
+            body
+        );
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(header(&requests[0], "range"), Some("bytes=2048-"));
+        assert_eq!(header(&requests[1], "range"), None);
     }
 }
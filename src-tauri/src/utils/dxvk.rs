@@ -0,0 +1,191 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use log::{info, warn};
+use tauri::Window;
+
+use crate::{
+    types::{
+        archive::ExtractOptions,
+        error::CustomError,
+        runner::{DxvkRelease, DxvkSource},
+    },
+    utils::{
+        archive::{archive_base_name, extract_archive, scan_archive},
+        downloader::{download_using_url, fetch_published_checksum, fetch_releases, HttpClient},
+        emitter::report_extract_progress,
+        fs::cleanup_file,
+    },
+};
+
+//github's page limit, the builds setup installs are further back than the settings list
+const DXVK_LOOKUP_RELEASES: u32 = 100;
+
+pub fn list_dxvk_releases(
+    client: &HttpClient,
+    sources: &[DxvkSource],
+    releases_per_source: u32,
+    dxvk_dir: &Path,
+) -> Result<Vec<DxvkRelease>, CustomError> {
+    let mut releases = Vec::new();
+    let mut last_error = None;
+
+    for source in sources {
+        let source_releases = match fetch_releases(client, &source.repo, releases_per_source) {
+            Ok(source_releases) => source_releases,
+            Err(err) => {
+                warn!("Failed to list releases of {}: {}", source.repo, err);
+                last_error = Some(err);
+                continue;
+            }
+        };
+
+        for release in source_releases {
+            for asset in release
+                .assets
+                .iter()
+                .filter(|asset| asset.name.ends_with(&source.asset_suffix))
+            {
+                releases.push(DxvkRelease {
+                    source: source.name.clone(),
+                    tag_name: release.tag_name.clone(),
+                    asset_name: asset.name.clone(),
+                    download_url: asset.browser_download_url.clone(),
+                    size: asset.size,
+                    published_at: release.published_at.clone(),
+                    prerelease: release.prerelease,
+                    installed: dxvk_dir.join(archive_base_name(&asset.name)).is_dir(),
+                });
+            }
+        }
+    }
+
+    match last_error {
+        Some(err) if releases.is_empty() => Err(err),
+        _ => Ok(releases),
+    }
+}
+
+//a configured dxvk only records its folder name, the release is looked up in every source
+pub fn find_dxvk_release(
+    client: &HttpClient,
+    sources: &[DxvkSource],
+    dxvk_dir: &Path,
+    name: &str,
+) -> Result<Option<DxvkRelease>, CustomError> {
+    Ok(
+        list_dxvk_releases(client, sources, DXVK_LOOKUP_RELEASES, dxvk_dir)?
+            .into_iter()
+            .find(|release| archive_base_name(&release.asset_name) == name),
+    )
+}
+
+//returns the folder inside the archive that holds x32/ and x64/
+fn inspect_dxvk_archive(archive_path: &Path) -> Result<PathBuf, CustomError> {
+    let mut root: Option<PathBuf> = None;
+
+    scan_archive(archive_path, &mut |path, _| {
+        let is_dll = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("dll"));
+
+        if let Some(arch_dir) = path
+            .parent()
+            .filter(|parent| is_dll && parent.ends_with("x64"))
+        {
+            let dll_root = arch_dir.parent().unwrap_or(Path::new("")).to_path_buf();
+            if root
+                .as_ref()
+                .is_none_or(|root| dll_root.components().count() < root.components().count())
+            {
+                root = Some(dll_root);
+            }
+        }
+
+        Ok(())
+    })?;
+
+    root.ok_or_else(|| {
+        CustomError::UnsupportedArchive(format!(
+            "{} does not contain a dxvk build",
+            archive_path.display()
+        ))
+    })
+}
+
+pub fn install_dxvk_archive(
+    archive_path: &Path,
+    dxvk_dir: &Path,
+    window: &Window,
+) -> Result<String, CustomError> {
+    let file_name = archive_path
+        .file_name()
+        .ok_or_else(|| CustomError::PathError("Invalid archive path".to_string()))?
+        .to_string_lossy()
+        .into_owned();
+
+    let root = inspect_dxvk_archive(archive_path)?;
+    let name = root
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| archive_base_name(&file_name).to_string());
+
+    let target_dir = dxvk_dir.join(&name);
+    if target_dir.exists() {
+        return Err(CustomError::PathError(format!(
+            "Version already exists at {}",
+            target_dir.display()
+        )));
+    }
+    fs::create_dir_all(&target_dir)?;
+
+    extract_archive(
+        archive_path,
+        &target_dir,
+        &ExtractOptions {
+            root: Some(root.clone()),
+            ..Default::default()
+        },
+        &mut |progress| {
+            report_extract_progress(window, progress).ok();
+        },
+    )
+    .inspect_err(|_| {
+        fs::remove_dir_all(&target_dir).ok();
+    })?;
+
+    info!("Added dxvk {} from archive: {}", name, file_name);
+
+    Ok(name)
+}
+
+pub fn download_dxvk(
+    client: &HttpClient,
+    release: &DxvkRelease,
+    dxvk_dir: &Path,
+    downloads_dir: &Path,
+    window: &Window,
+) -> Result<String, CustomError> {
+    //shares the runner downloads folder so .part files never show up as dxvk versions
+    fs::create_dir_all(downloads_dir)?;
+
+    let checksum = fetch_published_checksum(client, &release.download_url, &release.asset_name)?;
+
+    let archive_path = download_using_url(
+        client,
+        &release.download_url,
+        downloads_dir,
+        &release.asset_name,
+        checksum.as_deref(),
+        window,
+        None,
+    )?;
+
+    let name = install_dxvk_archive(&archive_path, dxvk_dir, window);
+
+    cleanup_file(&archive_path)?;
+
+    name
+}
@@ -1,6 +1,8 @@
 use tauri::{Emitter, Window};
 
-use crate::types::{error::CustomError, wizard::WizardStep};
+use crate::types::{
+    archive::ExtractProgress, download::DownloadProgress, error::CustomError, wizard::WizardStep,
+};
 
 pub fn report_step(window: &Window, step: WizardStep) -> Result<(), CustomError> {
     window.emit("wizard-step", step)?;
@@ -11,3 +13,19 @@ pub fn report_event(window: &Window, event: &str, payload: &str) -> Result<(), C
     window.emit(event, payload)?;
     Ok(())
 }
+
+pub fn report_download_progress(
+    window: &Window,
+    progress: DownloadProgress,
+) -> Result<(), CustomError> {
+    window.emit("download-progress", progress)?;
+    Ok(())
+}
+
+pub fn report_extract_progress(
+    window: &Window,
+    progress: ExtractProgress,
+) -> Result<(), CustomError> {
+    window.emit("extract-progress", progress)?;
+    Ok(())
+}
@@ -2,7 +2,6 @@ use log::info;
 use std::fs;
 use std::os::unix::fs as unix_fs;
 use std::path::Path;
-use std::process::Command;
 
 use crate::types::error::CustomError;
 
@@ -16,6 +15,36 @@ pub fn cleanup_dir(dir_path: &Path, dir_name: &str) -> Result<(), CustomError> {
     Ok(())
 }
 
+pub fn cleanup_dir_except(
+    dir_path: &Path,
+    dir_name: &str,
+    keep: &[&str],
+) -> Result<(), CustomError> {
+    //Clean up the directory's contents but leave the entries in keep alone
+    if !dir_path.exists() {
+        return Ok(());
+    }
+
+    info!("Cleaning up existing {0}, keeping {1:?}", dir_name, keep);
+
+    for entry in fs::read_dir(dir_path)? {
+        let entry = entry?;
+        let path = entry.path();
+
+        if keep.iter().any(|name| entry.file_name() == *name) {
+            continue;
+        }
+
+        if path.is_dir() && !path.is_symlink() {
+            fs::remove_dir_all(&path)?;
+        } else {
+            fs::remove_file(&path)?;
+        }
+    }
+
+    Ok(())
+}
+
 pub fn cleanup_file(file_path: &Path) -> Result<(), CustomError> {
     if file_path.exists() {
         info!(
@@ -28,56 +57,29 @@ pub fn cleanup_file(file_path: &Path) -> Result<(), CustomError> {
     Ok(())
 }
 
-pub fn extract_tarball(file: &Path, dest: &Path) -> Result<(), CustomError> {
-    //Function to extract tarballs
+pub fn dir_size(dir_path: &Path) -> Result<u64, CustomError> {
+    //symlinks count as themselves, so links into other versions or the prefix aren't followed
+    let mut size = 0;
 
-    let file_str = file.to_str().expect("Invalid file path");
-    let dest_str = dest.to_str().expect("Invalid destination path");
+    for entry in fs::read_dir(dir_path)? {
+        let path = entry?.path();
+        let metadata = path.symlink_metadata()?;
 
-    Command::new("tar")
-        .args(&["-xf", file_str, "-C", dest_str, "--strip-components=1"])
-        .status()?;
-
-    Ok(())
-}
-
-pub fn extract_zip(file: &Path, dest: &Path) -> Result<(), CustomError> {
-    //Function to extract zip files
-
-    let file_str = file.to_str().expect("Invalid file path");
-    let dest_str = dest.to_str().expect("Invalid destination path");
-
-    Command::new("unzip")
-        .args(&[file_str, "-d", dest_str])
-        .status()?;
-
-    Ok(())
-}
-
-pub fn extract_archive(file: &Path, dest: &Path) -> Result<(), CustomError> {
-    // Wrapper function for extract_tar
-    // and extract_zip functions
-
-    let ext = file
-        .extension()
-        .and_then(|s| s.to_str())
-        .unwrap_or("")
-        .to_lowercase();
-
-    match ext.as_str() {
-        "zip" => extract_zip(file, dest),
-        "gz" | "xz" | "bz2" | "tar" => extract_tarball(file, dest),
-        other => Err(std::io::Error::new(
-            std::io::ErrorKind::InvalidInput,
-            format!("Unsupported archive format: {}", other),
-        ))?,
+        size += if metadata.is_dir() {
+            dir_size(&path)?
+        } else {
+            metadata.len()
+        };
     }
+
+    Ok(size)
 }
 
 pub fn create_symlinks_by_filename(
     source_dir: &Path,
     dest_dir: &Path,
     forceable: bool,
+    excluded: &[&str],
 ) -> Result<(), CustomError> {
     for entry in fs::read_dir(source_dir)? {
         let entry = entry?;
@@ -88,15 +90,62 @@ pub fn create_symlinks_by_filename(
                 Some(name) => name,
                 None => continue,
             };
+            if excluded
+                .iter()
+                .any(|excluded_name| file_name == *excluded_name)
+            {
+                continue;
+            }
 
             let source_file_path = source_dir.join(&file_name);
             let dest_file_path = dest_dir.join(&file_name);
 
-            if forceable && dest_file_path.exists() {
-                fs::remove_file(&dest_file_path)?;
+            if forceable {
+                //renaming over the old file swaps it in one step, also replaces dangling links
+                let temp_file_path = dest_dir.join(format!(".{}.tmp", file_name.to_string_lossy()));
+                if fs::symlink_metadata(&temp_file_path).is_ok() {
+                    fs::remove_file(&temp_file_path)?;
+                }
+                unix_fs::symlink(&source_file_path, &temp_file_path)?;
+                fs::rename(&temp_file_path, &dest_file_path)?;
+            } else {
+                unix_fs::symlink(&source_file_path, &dest_file_path)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn copy_files_by_filename(
+    source_dir: &Path,
+    dest_dir: &Path,
+    excluded: &[&str],
+) -> Result<(), CustomError> {
+    for entry in fs::read_dir(source_dir)? {
+        let path = entry?.path();
+
+        if path.is_file() {
+            let file_name = match path.file_name() {
+                Some(name) => name,
+                None => continue,
+            };
+            if excluded
+                .iter()
+                .any(|excluded_name| file_name == *excluded_name)
+            {
+                continue;
             }
 
-            unix_fs::symlink(&source_file_path, &dest_file_path)?;
+            //copying next to the target first so a failed copy never leaves a truncated file
+            let dest_file_path = dest_dir.join(file_name);
+            let temp_file_path = dest_dir.join(format!(".{}.tmp", file_name.to_string_lossy()));
+            //a leftover link here would make the copy write into the link's target
+            if fs::symlink_metadata(&temp_file_path).is_ok() {
+                fs::remove_file(&temp_file_path)?;
+            }
+            fs::copy(&path, &temp_file_path)?;
+            fs::rename(&temp_file_path, &dest_file_path)?;
         }
     }
 
@@ -4,7 +4,12 @@ use anyhow::anyhow;
 use once_cell::sync::OnceCell;
 use which::which;
 
-use crate::types::{config::Config, error::CustomError};
+use crate::types::{
+    config::{
+        Config, D3D9Component, DxvkInstallMode, KeyValue, NetworkComponent, RunnersComponent,
+    },
+    error::CustomError,
+};
 
 static CONFIG: OnceCell<RwLock<Config>> = OnceCell::new();
 static COMMAND_EXISTENCE: OnceCell<RwLock<HashMap<String, bool>>> = OnceCell::new();
@@ -36,6 +41,32 @@ pub fn get_config() -> Result<Config, CustomError> {
     Ok(cfg.clone())
 }
 
+pub fn get_network_settings() -> NetworkComponent {
+    //setup downloads run before config.json exists, those use the defaults
+    get_config().map(|cfg| cfg.network).unwrap_or_default()
+}
+
+pub fn get_runner_settings() -> RunnersComponent {
+    get_config().map(|cfg| cfg.runners).unwrap_or_default()
+}
+
+pub fn get_d3d9_settings() -> D3D9Component {
+    get_config().map(|cfg| cfg.d3d9).unwrap_or_default()
+}
+
+//empty unless the user chose to keep the overrides in the prefix registry
+pub fn get_persisted_dll_overrides() -> Vec<KeyValue> {
+    get_config()
+        .map(|cfg| cfg.game.persisted_dll_overrides().to_vec())
+        .unwrap_or_default()
+}
+
+pub fn get_dxvk_install_mode() -> DxvkInstallMode {
+    get_config()
+        .map(|cfg| cfg.dxvk.install_mode)
+        .unwrap_or_default()
+}
+
 pub fn update_config(new_cfg: Config, config_path: &Path) -> Result<(), CustomError> {
     let lock = CONFIG
         .get()
@@ -55,7 +86,7 @@ pub fn update_config(new_cfg: Config, config_path: &Path) -> Result<(), CustomEr
 pub fn init_command_checks() -> Result<(), CustomError> {
     let mut is_commands_available: HashMap<String, bool> = HashMap::new();
 
-    let commands = ["gamemoderun", "mangohud"];
+    let commands = ["gamemoderun", "mangohud", "umu-run", "winetricks"];
 
     for command in commands {
         let status = which(command).is_ok();
@@ -1,5 +1,18 @@
+pub mod archive;
+pub mod cache;
+pub mod cancel;
+pub mod checksum;
 pub mod downloader;
+pub mod dxvk;
 pub mod emitter;
 pub mod fs;
 pub mod initialize;
+pub mod nine;
 pub mod process;
+pub mod registry;
+pub mod runner;
+pub mod search_paths;
+pub mod snapshot;
+#[cfg(test)]
+pub mod test_server;
+pub mod winetricks;
@@ -0,0 +1,158 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::anyhow;
+use log::info;
+use tauri::Window;
+
+use crate::{
+    setup::nine::NINE_DLL,
+    types::{archive::ExtractOptions, config::D3D9Component, error::CustomError},
+    utils::{
+        archive::{archive_base_name, extract_archive, scan_archive},
+        downloader::{
+            build_client, download_using_url, fetch_published_checksum, fetch_releases, HttpClient,
+        },
+        emitter::report_extract_progress,
+        fs::cleanup_file,
+        initialize::get_network_settings,
+    },
+};
+
+const NINE_REPO: &str = "iXit/wine-nine-standalone";
+const NINE_ASSET_SUFFIX: &str = ".tar.gz";
+
+//returns the folder inside the archive that holds lib32/ and lib64/
+fn inspect_nine_archive(archive_path: &Path) -> Result<PathBuf, CustomError> {
+    let mut root: Option<PathBuf> = None;
+
+    scan_archive(archive_path, &mut |path, _| {
+        if path.file_name().is_some_and(|name| name == NINE_DLL) {
+            if let Some(lib_dir) = path.parent().filter(|parent| parent.ends_with("lib32")) {
+                root.get_or_insert_with(|| lib_dir.parent().unwrap_or(Path::new("")).to_path_buf());
+            }
+        }
+
+        Ok(())
+    })?;
+
+    root.ok_or_else(|| {
+        CustomError::UnsupportedArchive(format!(
+            "{} does not contain a Gallium Nine build",
+            archive_path.display()
+        ))
+    })
+}
+
+//latest gallium nine standalone release, returns its folder under nine_dir
+pub fn download_nine(
+    client: &HttpClient,
+    nine_dir: &Path,
+    downloads_dir: &Path,
+    window: &Window,
+) -> Result<PathBuf, CustomError> {
+    let release = fetch_releases(client, NINE_REPO, 5)?
+        .into_iter()
+        .find(|release| !release.prerelease)
+        .ok_or_else(|| CustomError::Anyhow(anyhow!("No Gallium Nine release found")))?;
+    let asset = release
+        .assets
+        .iter()
+        .find(|asset| asset.name.ends_with(NINE_ASSET_SUFFIX))
+        .ok_or_else(|| {
+            CustomError::Anyhow(anyhow!(
+                "Gallium Nine {} has no {} asset",
+                release.tag_name,
+                NINE_ASSET_SUFFIX
+            ))
+        })?;
+
+    let target_dir = nine_dir.join(archive_base_name(&asset.name));
+    if target_dir.join("lib32").join(NINE_DLL).is_file() {
+        return Ok(target_dir);
+    }
+
+    fs::create_dir_all(downloads_dir)?;
+    let checksum = fetch_published_checksum(client, &asset.browser_download_url, &asset.name)?;
+    let archive_path = download_using_url(
+        client,
+        &asset.browser_download_url,
+        downloads_dir,
+        &asset.name,
+        checksum.as_deref(),
+        window,
+        None,
+    )?;
+
+    let installed = install_nine_archive(&archive_path, &target_dir, window);
+
+    cleanup_file(&archive_path)?;
+
+    installed?;
+
+    info!("Added Gallium Nine {}", release.tag_name);
+
+    Ok(target_dir)
+}
+
+//downloads nine when the configured build is gone, true when the settings changed
+pub fn ensure_nine_build(
+    d3d9: &mut D3D9Component,
+    app_dir: &Path,
+    window: &Window,
+) -> Result<bool, CustomError> {
+    if Path::new(&d3d9.nine_path)
+        .join("lib32")
+        .join(NINE_DLL)
+        .is_file()
+    {
+        return Ok(false);
+    }
+
+    let client = build_client(&get_network_settings(), &app_dir.join("cache"))?;
+    let nine_path = download_nine(
+        &client,
+        &app_dir.join("runtime/nine"),
+        &app_dir.join("runners/downloads"),
+        window,
+    )?;
+
+    d3d9.nine_version = nine_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    d3d9.nine_path = nine_path.display().to_string();
+
+    Ok(true)
+}
+
+fn install_nine_archive(
+    archive_path: &Path,
+    target_dir: &Path,
+    window: &Window,
+) -> Result<(), CustomError> {
+    let root = inspect_nine_archive(archive_path)?;
+
+    //a half extracted build would otherwise pass as installed
+    if target_dir.exists() {
+        fs::remove_dir_all(target_dir)?;
+    }
+    fs::create_dir_all(target_dir)?;
+
+    extract_archive(
+        archive_path,
+        target_dir,
+        &ExtractOptions {
+            root: Some(root.clone()),
+            ..Default::default()
+        },
+        &mut |progress| {
+            report_extract_progress(window, progress).ok();
+        },
+    )
+    .inspect_err(|_| {
+        fs::remove_dir_all(target_dir).ok();
+    })
+}
@@ -1,16 +1,23 @@
-use log::error;
+use log::{error, info};
 use std::{
+    fs,
+    io::{BufRead, BufReader, Read},
     path::Path,
-    process::{Command, Stdio},
+    process::{Child, Command, ExitStatus, Stdio},
+    sync::mpsc,
+    thread, time,
 };
 
-use crate::types::error::CustomError;
+use anyhow::anyhow;
+
+use crate::{types::error::CustomError, utils::cancel::CancellationToken};
 
 pub fn run_command<P: AsRef<std::ffi::OsStr>>(
     program: P,
     args: Option<&[&str]>,
     envs: Option<&[(&str, &str)]>,
     cwd: Option<&Path>,
+    token: Option<&CancellationToken>,
 ) -> Result<(), CustomError> {
     let mut cmd = Command::new(&program);
 
@@ -45,20 +52,179 @@ pub fn run_command<P: AsRef<std::ffi::OsStr>>(
         }
     }
 
-    let status = cmd.status()?;
+    let child = cmd.spawn()?;
+    let status = wait_for_child(child, token, &mut || {})?;
+
+    check_status(program.as_ref(), status)
+}
+
+//like run_command, but hands every stdout and stderr line to on_line while the command runs
+pub fn run_command_streamed<P: AsRef<std::ffi::OsStr>>(
+    program: P,
+    args: &[&str],
+    envs: &[(&str, &str)],
+    on_line: &mut dyn FnMut(&str),
+    token: Option<&CancellationToken>,
+) -> Result<(), CustomError> {
+    info!("Running command: {:?} {}", program.as_ref(), args.join(" "));
+
+    let mut child = Command::new(&program)
+        .args(args)
+        .envs(envs.iter().cloned())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
 
+    let (sender, receiver) = mpsc::channel::<String>();
+    let streams: [Option<Box<dyn Read + Send>>; 2] = [
+        child
+            .stdout
+            .take()
+            .map(|s| Box::new(s) as Box<dyn Read + Send>),
+        child
+            .stderr
+            .take()
+            .map(|s| Box::new(s) as Box<dyn Read + Send>),
+    ];
+    for stream in streams.into_iter().flatten() {
+        let sender = sender.clone();
+        thread::spawn(move || {
+            for line in BufReader::new(stream).lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+    drop(sender);
+
+    let status = wait_for_child(child, token, &mut || {
+        receiver.try_iter().for_each(|line| on_line(&line));
+    })?;
+
+    //a wineserver started by the command inherits the pipes and keeps them open,
+    //so the rest of the output is only waited for briefly
+    while let Ok(line) = receiver.recv_timeout(time::Duration::from_millis(500)) {
+        on_line(&line);
+    }
+
+    check_status(program.as_ref(), status)
+}
+
+//polling instead of blocking on wait() so a cancelled token can kill the child,
+//only the children started with that token are affected
+fn wait_for_child(
+    mut child: Child,
+    token: Option<&CancellationToken>,
+    on_poll: &mut dyn FnMut(),
+) -> Result<ExitStatus, CustomError> {
+    loop {
+        on_poll();
+
+        if token.is_some_and(CancellationToken::is_cancelled) {
+            info!("Killing cancelled command with pid {}", child.id());
+
+            //the child may have exited on its own in the meantime
+            if let Err(err) = child.kill() {
+                error!("Failed to kill process {}: {}", child.id(), err);
+            }
+            let _ = child.wait();
+
+            return Err(CustomError::Cancelled);
+        }
+
+        if let Some(status) = child.try_wait()? {
+            return Ok(status);
+        }
+
+        thread::sleep(time::Duration::from_millis(100));
+    }
+}
+
+fn check_status(program: &std::ffi::OsStr, status: ExitStatus) -> Result<(), CustomError> {
     if !status.success() {
-        error!(
-            "Command {:?} failed with status: {:?}",
-            program.as_ref(),
-            status
-        );
-        return Err(CustomError::Anyhow(anyhow::anyhow!(
+        error!("Command {:?} failed with status: {:?}", program, status);
+        return Err(CustomError::Anyhow(anyhow!(
             "Command {:?} failed with status: {:?}",
-            program.as_ref(),
+            program,
             status
         )));
     }
 
     Ok(())
 }
+
+//true when a running process executes from dir or has a file from it mapped,
+//wine runs its binaries from the runner and maps dxvk dlls through the prefix symlinks
+pub fn is_path_in_use(dir: &Path) -> bool {
+    let dir = match dir.canonicalize() {
+        Ok(dir) => dir,
+        Err(_) => return false,
+    };
+    let dir_str = dir.to_string_lossy().into_owned();
+
+    let proc_entries = match fs::read_dir("/proc") {
+        Ok(entries) => entries,
+        Err(_) => return false,
+    };
+
+    for entry in proc_entries.flatten() {
+        let is_pid = entry
+            .file_name()
+            .to_str()
+            .is_some_and(|name| name.chars().all(|c| c.is_ascii_digit()));
+        if !is_pid {
+            continue;
+        }
+
+        let proc_dir = entry.path();
+
+        //other users' processes aren't readable, those can't hold our files anyway
+        if fs::read_link(proc_dir.join("exe")).is_ok_and(|exe| exe.starts_with(&dir)) {
+            return true;
+        }
+
+        if let Ok(maps) = fs::read_to_string(proc_dir.join("maps")) {
+            let maps_file = maps
+                .lines()
+                .filter_map(mapped_path)
+                .any(|mapped| Path::new(mapped).starts_with(&dir_str));
+
+            if maps_file {
+                return true;
+            }
+        }
+    }
+
+    false
+}
+
+//maps lines are "address perms offset dev inode   path", the path itself may contain spaces
+fn mapped_path(line: &str) -> Option<&str> {
+    let path = line.splitn(6, ' ').nth(5)?.trim_start();
+
+    (!path.is_empty()).then_some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mapped_paths_keep_their_spaces() {
+        let maps = "7f1c2a000000-7f1c2a021000 r--p 00000000 103:02 1054321                   /home/user/My Games/nfsw prefix/drive_c/windows/system32/d3d9.dll
+7f1c2a200000-7f1c2a221000 rw-p 00000000 00:00 0 
+7ffd4e5fe000-7ffd4e61f000 rw-p 00000000 00:00 0                          [stack]";
+
+        let paths: Vec<&str> = maps.lines().filter_map(mapped_path).collect();
+
+        assert_eq!(
+            paths,
+            [
+                "/home/user/My Games/nfsw prefix/drive_c/windows/system32/d3d9.dll",
+                "[stack]"
+            ]
+        );
+        assert!(Path::new(paths[0]).starts_with("/home/user/My Games/nfsw prefix"));
+    }
+}
@@ -0,0 +1,844 @@
+use std::{
+    fs,
+    os::unix::{fs::MetadataExt, net::UnixStream},
+    path::{Path, PathBuf},
+};
+
+use crate::types::{
+    error::CustomError,
+    registry::{RegValue, RegistryFile, RegistryHive, RegistryKey},
+};
+
+const REGISTRY_VERSION_LINE: &str = "WINE REGISTRY Version 2";
+//wine breaks hex data after this many characters
+const HEX_LINE_WIDTH: usize = 76;
+//escape letters for control characters, same table as wine's dump_strW
+const CONTROL_ESCAPES: &[u8; 32] = b".......abtnvfr.............e....";
+
+const REG_SZ: u32 = 1;
+const REG_EXPAND_SZ: u32 = 2;
+const REG_BINARY: u32 = 3;
+const REG_DWORD: u32 = 4;
+const REG_MULTI_SZ: u32 = 7;
+const REG_QWORD: u32 = 0xb;
+
+//root keys a regedit file can name and the prefix file wine saves each of them to
+const REGEDIT_HIVES: [(&str, RegistryFile); 2] = [
+    ("HKEY_CURRENT_USER\\", RegistryFile::User),
+    ("HKEY_LOCAL_MACHINE\\", RegistryFile::System),
+];
+
+fn registry_error(line_number: usize, message: &str) -> CustomError {
+    CustomError::Registry(format!("line {}: {}", line_number, message))
+}
+
+//reads up to the unescaped terminator, returns the text and what follows the terminator
+fn unescape_until(input: &str, terminator: char) -> Option<(String, &str)> {
+    let mut units: Vec<u16> = Vec::new();
+    let mut chars = input.char_indices().peekable();
+
+    while let Some((index, c)) = chars.next() {
+        if c == terminator {
+            return Some((String::from_utf16_lossy(&units), &input[index + 1..]));
+        }
+
+        if c != '\\' {
+            units.extend(c.encode_utf16(&mut [0; 2]).iter());
+            continue;
+        }
+
+        let (_, escaped) = chars.next()?;
+        match escaped {
+            'a' => units.push(0x07),
+            'b' => units.push(0x08),
+            'e' => units.push(0x1b),
+            'f' => units.push(0x0c),
+            'n' => units.push(0x0a),
+            'r' => units.push(0x0d),
+            't' => units.push(0x09),
+            'v' => units.push(0x0b),
+            'x' => {
+                let mut unit: u16 = 0;
+                for _ in 0..4 {
+                    match chars.peek().and_then(|(_, digit)| digit.to_digit(16)) {
+                        Some(digit) => {
+                            unit = unit * 16 + digit as u16;
+                            chars.next();
+                        }
+                        None => break,
+                    }
+                }
+                units.push(unit);
+            }
+            '0'..='7' => {
+                let mut unit = escaped.to_digit(8)? as u16;
+                for _ in 0..2 {
+                    match chars.peek().and_then(|(_, digit)| digit.to_digit(8)) {
+                        Some(digit) => {
+                            unit = unit * 8 + digit as u16;
+                            chars.next();
+                        }
+                        None => break,
+                    }
+                }
+                units.push(unit);
+            }
+            other => units.extend(other.encode_utf16(&mut [0; 2]).iter()),
+        }
+    }
+
+    None
+}
+
+//same escaping wine uses when saving, so rewritten files only differ where values changed
+fn escape(input: &str, specials: &[u16]) -> String {
+    let units: Vec<u16> = input.encode_utf16().collect();
+    let mut escaped = String::with_capacity(units.len());
+
+    for (index, &unit) in units.iter().enumerate() {
+        let next = units.get(index + 1).copied();
+
+        if unit > 127 {
+            //a following hex digit would be read as part of the escape
+            if next.is_some_and(|n| n < 128 && (n as u8).is_ascii_hexdigit()) {
+                escaped.push_str(&format!("\\x{:04x}", unit));
+            } else {
+                escaped.push_str(&format!("\\x{:x}", unit));
+            }
+        } else if unit < 32 {
+            match CONTROL_ESCAPES[unit as usize] {
+                b'.' if next.is_some_and(|n| (b'0' as u16..=b'7' as u16).contains(&n)) => {
+                    escaped.push_str(&format!("\\{:03o}", unit))
+                }
+                b'.' => escaped.push_str(&format!("\\{:o}", unit)),
+                letter => {
+                    escaped.push('\\');
+                    escaped.push(letter as char);
+                }
+            }
+        } else {
+            if unit == b'\\' as u16 || specials.contains(&unit) {
+                escaped.push('\\');
+            }
+            escaped.push(unit as u8 as char);
+        }
+    }
+
+    escaped
+}
+
+fn utf16_bytes(text: &str) -> Vec<u8> {
+    text.encode_utf16()
+        .chain(std::iter::once(0))
+        .flat_map(u16::to_le_bytes)
+        .collect()
+}
+
+//string data has to be whole utf-16 units ending in a null, like wine checks before saving
+fn utf16_string(data: &[u8]) -> Option<String> {
+    if data.len() < 2 || !data.len().is_multiple_of(2) {
+        return None;
+    }
+
+    let units: Vec<u16> = data
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .collect();
+    let (last, text) = units.split_last()?;
+    (*last == 0).then(|| String::from_utf16_lossy(text))
+}
+
+fn split_multi_string(text: &str) -> Vec<String> {
+    if text.is_empty() {
+        return Vec::new();
+    }
+
+    text.strip_suffix('\0')
+        .unwrap_or(text)
+        .split('\0')
+        .map(str::to_string)
+        .collect()
+}
+
+fn join_multi_string(items: &[String]) -> String {
+    items.iter().map(|item| format!("{}\0", item)).collect()
+}
+
+fn typed_value(kind: u32, data: Vec<u8>) -> RegValue {
+    match kind {
+        REG_SZ | REG_EXPAND_SZ | REG_MULTI_SZ => match utf16_string(&data) {
+            Some(text) if kind == REG_SZ => RegValue::String(text),
+            Some(text) if kind == REG_EXPAND_SZ => RegValue::ExpandString(text),
+            Some(text) => RegValue::MultiString(split_multi_string(&text)),
+            None => RegValue::Raw { kind, data },
+        },
+        REG_DWORD if data.len() == 4 => {
+            RegValue::Dword(u32::from_le_bytes([data[0], data[1], data[2], data[3]]))
+        }
+        REG_QWORD if data.len() == 8 => {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(&data);
+            RegValue::Qword(u64::from_le_bytes(bytes))
+        }
+        REG_BINARY => RegValue::Binary(data),
+        _ => RegValue::Raw { kind, data },
+    }
+}
+
+fn parse_hex_bytes(input: &str) -> Option<Vec<u8>> {
+    input
+        .split(',')
+        .map(|byte| byte.trim_matches(|c: char| c.is_whitespace() || c == '\\'))
+        .filter(|byte| !byte.is_empty())
+        .map(|byte| u8::from_str_radix(byte, 16).ok())
+        .collect()
+}
+
+fn parse_data(data: &str) -> Option<RegValue> {
+    if let Some(quoted) = data.strip_prefix('"') {
+        let (text, _) = unescape_until(quoted, '"')?;
+        return Some(RegValue::String(text));
+    }
+
+    if let Some(rest) = data.strip_prefix("str(") {
+        let (kind, quoted) = rest.split_once("):\"")?;
+        let kind = u32::from_str_radix(kind, 16).ok()?;
+        let (text, _) = unescape_until(quoted, '"')?;
+        return Some(match kind {
+            REG_SZ => RegValue::String(text),
+            REG_EXPAND_SZ => RegValue::ExpandString(text),
+            REG_MULTI_SZ => RegValue::MultiString(split_multi_string(&text)),
+            _ => RegValue::Raw {
+                kind,
+                data: utf16_bytes(&text),
+            },
+        });
+    }
+
+    if let Some(value) = data.strip_prefix("dword:") {
+        return u32::from_str_radix(value.trim(), 16)
+            .ok()
+            .map(RegValue::Dword);
+    }
+
+    if let Some(bytes) = data.strip_prefix("hex:") {
+        return parse_hex_bytes(bytes).map(RegValue::Binary);
+    }
+
+    let (kind, bytes) = data.strip_prefix("hex(")?.split_once("):")?;
+    let kind = u32::from_str_radix(kind, 16).ok()?;
+    Some(typed_value(kind, parse_hex_bytes(bytes)?))
+}
+
+//splits a value line into its unescaped name and the data after the =
+fn split_value_line(line: &str) -> Option<(String, &str)> {
+    let (name, rest) = match line.strip_prefix('@') {
+        Some(rest) => (String::new(), rest),
+        None => unescape_until(line.strip_prefix('"')?, '"')?,
+    };
+
+    Some((name, rest.trim_start().strip_prefix('=')?.trim_start()))
+}
+
+fn parse_value(line: &str) -> Option<(String, RegValue)> {
+    let (name, data) = split_value_line(line)?;
+
+    Some((name, parse_data(data)?))
+}
+
+//only hex data is wrapped, a string may end in a backslash or contain =hex itself
+fn continues_on_next_line(line: &str) -> bool {
+    line.ends_with('\\') && split_value_line(line).is_some_and(|(_, data)| data.starts_with("hex"))
+}
+
+fn parse_key_line(line: &str) -> Option<RegistryKey> {
+    let (path, rest) = unescape_until(line.strip_prefix('[')?, ']')?;
+
+    Some(RegistryKey {
+        path,
+        modified: rest.trim().parse().unwrap_or(0),
+        ..Default::default()
+    })
+}
+
+pub fn parse_registry(content: &str) -> Result<RegistryHive, CustomError> {
+    let mut hive = RegistryHive::default();
+    let mut lines = content.lines().enumerate();
+
+    match lines.next() {
+        Some((_, line)) if line.trim_end() == REGISTRY_VERSION_LINE => {
+            hive.header.push(REGISTRY_VERSION_LINE.to_string())
+        }
+        _ => return Err(registry_error(1, "not a wine registry file")),
+    }
+
+    while let Some((index, line)) = lines.next() {
+        let line_number = index + 1;
+        let mut line = line.trim_end().to_string();
+
+        if line.starts_with('[') {
+            let key = parse_key_line(&line)
+                .ok_or_else(|| registry_error(line_number, "malformed key"))?;
+            hive.keys.push(key);
+            continue;
+        }
+
+        let Some(key) = hive.keys.last_mut() else {
+            hive.header.push(line);
+            continue;
+        };
+
+        if line.is_empty() || line.starts_with(';') {
+            continue;
+        }
+
+        if let Some(time) = line.strip_prefix("#time=") {
+            key.time = Some(
+                u64::from_str_radix(time, 16)
+                    .map_err(|_| registry_error(line_number, "malformed #time"))?,
+            );
+            continue;
+        }
+
+        if line.starts_with('#') {
+            key.attributes.push(line);
+            continue;
+        }
+
+        //long hex data continues on the next lines
+        while continues_on_next_line(&line) {
+            match lines.next() {
+                Some((_, next)) => line.push_str(next.trim()),
+                None => break,
+            }
+        }
+
+        let value =
+            parse_value(&line).ok_or_else(|| registry_error(line_number, "malformed value"))?;
+        key.values.push(value);
+    }
+
+    while hive
+        .header
+        .last()
+        .is_some_and(|line| line.trim().is_empty())
+    {
+        hive.header.pop();
+    }
+
+    Ok(hive)
+}
+
+fn write_hex(output: &mut String, prefix: &str, data: &[u8]) {
+    output.push_str(prefix);
+    let mut count = prefix.len();
+
+    for (index, byte) in data.iter().enumerate() {
+        output.push_str(&format!("{:02x}", byte));
+        count += 2;
+
+        if index < data.len() - 1 {
+            output.push(',');
+            count += 1;
+            if count > HEX_LINE_WIDTH {
+                output.push_str("\\\n  ");
+                count = 2;
+            }
+        }
+    }
+}
+
+fn write_value(output: &mut String, name: &str, value: &RegValue) {
+    let name = if name.is_empty() {
+        "@".to_string()
+    } else {
+        format!("\"{}\"", escape(name, &[b'"' as u16]))
+    };
+    let mut prefix = format!("{}=", name);
+
+    match value {
+        RegValue::String(text) => {
+            prefix.push_str(&format!("\"{}\"", escape(text, &[b'"' as u16])));
+            output.push_str(&prefix);
+        }
+        RegValue::ExpandString(text) => {
+            prefix.push_str(&format!("str(2):\"{}\"", escape(text, &[b'"' as u16])));
+            output.push_str(&prefix);
+        }
+        RegValue::MultiString(items) => {
+            let text = join_multi_string(items);
+            prefix.push_str(&format!("str(7):\"{}\"", escape(&text, &[b'"' as u16])));
+            output.push_str(&prefix);
+        }
+        RegValue::Dword(number) => {
+            prefix.push_str(&format!("dword:{:08x}", number));
+            output.push_str(&prefix);
+        }
+        RegValue::Qword(number) => {
+            write_hex(output, &format!("{}hex(b):", prefix), &number.to_le_bytes())
+        }
+        RegValue::Binary(data) => write_hex(output, &format!("{}hex:", prefix), data),
+        RegValue::Raw { kind, data } => {
+            write_hex(output, &format!("{}hex({:x}):", prefix, kind), data)
+        }
+    }
+
+    output.push('\n');
+}
+
+pub fn serialize_registry(hive: &RegistryHive) -> String {
+    let mut output = String::new();
+
+    for line in &hive.header {
+        output.push_str(line);
+        output.push('\n');
+    }
+
+    for key in &hive.keys {
+        output.push_str(&format!(
+            "\n[{}] {}\n",
+            escape(&key.path, &[b'[' as u16, b']' as u16]),
+            key.modified
+        ));
+        if let Some(time) = key.time {
+            output.push_str(&format!("#time={:x}\n", time));
+        }
+        for attribute in &key.attributes {
+            output.push_str(attribute);
+            output.push('\n');
+        }
+        for (name, value) in &key.values {
+            write_value(&mut output, name, value);
+        }
+    }
+
+    output
+}
+
+pub fn read_registry(path: &Path) -> Result<RegistryHive, CustomError> {
+    let bytes = fs::read(path)?;
+
+    parse_registry(&String::from_utf8_lossy(&bytes)).map_err(|e| match e {
+        CustomError::Registry(message) => {
+            CustomError::Registry(format!("{}: {}", path.display(), message))
+        }
+        other => other,
+    })
+}
+
+//written next to the original and renamed over it, like wine does itself
+pub fn write_registry(path: &Path, hive: &RegistryHive) -> Result<(), CustomError> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| CustomError::PathError(format!("Invalid registry path {:?}", path)))?;
+    let temp_path = path.with_file_name(format!(".{}.tmp", file_name.to_string_lossy()));
+
+    fs::write(&temp_path, serialize_registry(hive))?;
+    fs::rename(&temp_path, path)?;
+
+    Ok(())
+}
+
+//values a regedit import file sets, as (file, key, name, value)
+//deletions and hives wine doesn't keep in user.reg or system.reg are left out
+pub fn parse_regedit_file(content: &str) -> Vec<(RegistryFile, String, String, RegValue)> {
+    let mut entries = Vec::new();
+    let mut current_key: Option<(RegistryFile, String)> = None;
+    let mut lines = content.lines();
+
+    while let Some(line) = lines.next() {
+        let mut line = line.trim().to_string();
+
+        if let Some(path) = line.strip_prefix('[') {
+            let path = path.strip_suffix(']').unwrap_or(path);
+            current_key = REGEDIT_HIVES.iter().find_map(|(hive, file)| {
+                path.strip_prefix(hive)
+                    .map(|key_path| (*file, key_path.to_string()))
+            });
+            continue;
+        }
+
+        let Some((file, key_path)) = &current_key else {
+            continue;
+        };
+
+        while continues_on_next_line(&line) {
+            match lines.next() {
+                Some(next) => line.push_str(next.trim()),
+                None => break,
+            }
+        }
+
+        if let Some((name, value)) = parse_value(&line) {
+            entries.push((*file, key_path.clone(), name, value));
+        }
+    }
+
+    entries
+}
+
+//regedit exports are utf-16 with a byte order mark, hand written ones usually utf-8
+pub fn read_regedit_file(
+    path: &Path,
+) -> Result<Vec<(RegistryFile, String, String, RegValue)>, CustomError> {
+    let bytes = fs::read(path)?;
+
+    let content = match bytes.strip_prefix(&[0xff, 0xfe]) {
+        Some(utf16) => String::from_utf16_lossy(
+            &utf16
+                .chunks_exact(2)
+                .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+                .collect::<Vec<_>>(),
+        ),
+        None => String::from_utf8_lossy(bytes.strip_prefix(&[0xef, 0xbb, 0xbf]).unwrap_or(&bytes))
+            .into_owned(),
+    };
+
+    Ok(parse_regedit_file(&content))
+}
+
+//wineserver keeps the registry in memory and overwrites the files when it exits,
+//its socket lives under /tmp/.wine-<uid>/server-<dev>-<inode> of the prefix
+pub fn is_wineserver_running(wine_prefix: &Path) -> bool {
+    let (Ok(prefix), Ok(process)) = (fs::metadata(wine_prefix), fs::metadata("/proc/self")) else {
+        return false;
+    };
+
+    let socket_path = PathBuf::from(format!("/tmp/.wine-{}", process.uid()))
+        .join(format!("server-{:x}-{:x}", prefix.dev(), prefix.ino()))
+        .join("socket");
+
+    UnixStream::connect(socket_path).is_ok()
+}
+
+pub fn read_prefix_registry(
+    wine_prefix: &Path,
+    file: RegistryFile,
+) -> Result<RegistryHive, CustomError> {
+    read_registry(&wine_prefix.join(file.file_name()))
+}
+
+//edits made while wineserver runs would be lost, so the prefix has to be stopped
+pub fn edit_prefix_registry<F>(
+    wine_prefix: &Path,
+    file: RegistryFile,
+    edit: F,
+) -> Result<(), CustomError>
+where
+    F: FnOnce(&mut RegistryHive),
+{
+    if is_wineserver_running(wine_prefix) {
+        return Err(CustomError::InUse {
+            name: file.file_name().to_string(),
+            user: "a running wineserver".to_string(),
+        });
+    }
+
+    let registry_path = wine_prefix.join(file.file_name());
+    let mut hive = read_registry(&registry_path)?;
+    edit(&mut hive);
+    write_registry(&registry_path, &hive)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //laid out the way wine's server saves a prefix, every line has to survive a round trip
+    const USER_REG: &str = r##"WINE REGISTRY Version 2
+;; All keys relative to \\User\\S-1-5-21-0-0-0-1000
+
+#arch=win64
+
+[Console] 1718031234
+#time=1dabc6f3e5a2b10
+"CursorSize"=dword:00000019
+"FaceName"=""
+"ScreenColors"=dword:0000000f
+
+[Control Panel\\Desktop] 1718031234
+#time=1dabc6f3e5a2b10
+"DragFullWindows"="0"
+"UserPreferencesMask"=hex:10,00,02,80,10,00,00,00
+
+[Control Panel\\International] 1718031240
+#time=1dabc6f3e9c4d20
+"s1159"="AM"
+"sCurrency"="\x20ac"
+"sLongDate"="dddd, MMMM d, yyyy"
+
+[Environment] 1718031234
+#time=1dabc6f3e5a2b10
+"TEMP"=str(2):"%USERPROFILE%\\AppData\\Local\\Temp"
+"TMP"=str(2):"%USERPROFILE%\\AppData\\Local\\Temp"
+
+[Software\\Wine\\DllOverrides] 1718031301
+#time=1dabc6f6a1b2c30
+"*d3d11"="native"
+"dinput8"="native,builtin"
+
+[Software\\Wine\\Explorer\\Desktops] 1718031301
+#time=1dabc6f6a1b2c30
+"Default"="1920x1080"
+
+[Volatile Environment] 1718031234
+#time=1dabc6f3e5a2b10
+@=hex:00,01,02,03,04,05,06,07,08,09,0a,0b,0c,0d,0e,0f,10,11,12,13,14,15,16,17,\
+  18,19,1a,1b,1c,1d,1e,1f,20,21,22,23,24,25,26,27
+"Control"="\0017x\1"
+"Empty"=hex(0):
+"HOMEDRIVE"="C:"
+"HOMEPATH"="\\users\\steamuser"
+"Multi"=str(7):"first\0second\0"
+"Name with \"quotes\" and \x00e91"="tab\there"
+"#time=fake"="not a key attribute"
+"##;
+
+    const SYSTEM_REG: &str = r##"WINE REGISTRY Version 2
+;; All keys relative to \\Machine
+
+#arch=win64
+
+[Software\\Classes\\.exe] 1718031234
+#time=1dabc6f3e5a2b10
+@="exefile"
+"Content Type"="application/x-msdownload"
+
+[Software\\Classes\\CLSID] 1718031234
+#time=1dabc6f3e5a2b10
+
+[Software\\Microsoft\\Windows NT\\CurrentVersion] 1718031234
+#time=1dabc6f3e5a2b10
+"CurrentBuildNumber"="19045"
+"InstallTime"=hex(b):08,f7,e6,d5,c4,b3,da,01
+"ProductName"="Windows 10 Pro"
+
+[Software\\Wow6432Node\\Microsoft\\VisualStudio\\14.0\\VC\\Runtimes\\x86] 1718031299
+#time=1dabc6f69f0e1a0
+"Installed"=dword:00000001
+"Version"="v14.40.33810.00"
+
+[System\\CurrentControlSet] 1718031234
+#time=1dabc6f3e5a2b10
+#link
+"SymbolicLinkValue"=hex(6):5c,00,52,00,65,00,67,00,69,00,73,00,74,00,72,00,79,\
+  00,5c,00,4d,00,61,00,63,00,68,00,69,00,6e,00,65,00,5c,00,53,00,79,00,73,00,\
+  74,00,65,00,6d,00,5c,00,43,00,6f,00,6e,00,74,00,72,00,6f,00,6c,00,53,00,65,\
+  00,74,00,30,00,30,00,31,00
+
+[System\\CurrentControlSet\\Control\\Network] 1718031234
+#time=1dabc6f3e5a2b10
+#class="Network \"adapters\""
+"DependOnService"=hex(7):52,00,50,00,43,00,53,00,53,00,00,00,54,00,63,00,70,00,\
+  69,00,70,00
+"##;
+
+    #[test]
+    fn user_reg_round_trips_byte_for_byte() {
+        let hive = parse_registry(USER_REG).unwrap();
+        assert_eq!(serialize_registry(&hive), USER_REG);
+    }
+
+    #[test]
+    fn system_reg_round_trips_byte_for_byte() {
+        let hive = parse_registry(SYSTEM_REG).unwrap();
+        assert_eq!(serialize_registry(&hive), SYSTEM_REG);
+    }
+
+    #[test]
+    fn parses_typed_values() {
+        let user_reg = parse_registry(USER_REG).unwrap();
+        let system_reg = parse_registry(SYSTEM_REG).unwrap();
+
+        assert_eq!(
+            user_reg.get_string(r"Software\Wine\DllOverrides", "DINPUT8"),
+            Some("native,builtin")
+        );
+        assert_eq!(
+            user_reg.get_value("Environment", "TEMP"),
+            Some(&RegValue::ExpandString(
+                r"%USERPROFILE%\AppData\Local\Temp".to_string()
+            ))
+        );
+        assert_eq!(
+            user_reg.get_value("Volatile Environment", "Multi"),
+            Some(&RegValue::MultiString(vec![
+                "first".to_string(),
+                "second".to_string()
+            ]))
+        );
+        assert_eq!(
+            user_reg.get_value("Volatile Environment", ""),
+            Some(&RegValue::Binary((0..40).collect()))
+        );
+        assert_eq!(
+            user_reg.get_string("Control Panel\\International", "sCurrency"),
+            Some("\u{20ac}")
+        );
+        assert_eq!(
+            user_reg.get_string("Volatile Environment", "Control"),
+            Some("\u{1}7x\u{1}")
+        );
+        assert_eq!(
+            user_reg.get_string("Volatile Environment", "Name with \"quotes\" and \u{e9}1"),
+            Some("tab\there")
+        );
+        assert_eq!(
+            user_reg.key("Console").unwrap().time,
+            Some(0x1dabc6f3e5a2b10)
+        );
+
+        assert_eq!(
+            system_reg.get_dword(
+                r"Software\Wow6432Node\Microsoft\VisualStudio\14.0\VC\Runtimes\x86",
+                "Installed"
+            ),
+            Some(1)
+        );
+        assert_eq!(
+            system_reg.get_value(
+                r"Software\Microsoft\Windows NT\CurrentVersion",
+                "InstallTime"
+            ),
+            Some(&RegValue::Qword(0x01dab3c4d5e6f708))
+        );
+        let link = system_reg.key(r"System\CurrentControlSet").unwrap();
+        assert_eq!(link.attributes, vec!["#link".to_string()]);
+        assert!(matches!(
+            link.value("SymbolicLinkValue"),
+            Some(RegValue::Raw { kind: 6, data }) if data.len() == 76
+        ));
+        //missing its terminating null, so it stays raw instead of becoming a multi string
+        assert!(matches!(
+            system_reg.get_value(
+                r"System\CurrentControlSet\Control\Network",
+                "DependOnService"
+            ),
+            Some(RegValue::Raw { kind: 7, .. })
+        ));
+    }
+
+    #[test]
+    fn escapes_edge_cases_like_wine() {
+        let cases = [
+            //a hex digit after a \x escape forces the 4 digit form
+            ("\u{e9}1", r"\x00e91"),
+            ("\u{e9}g", r"\xe9g"),
+            ("\u{e9}", r"\xe9"),
+            ("\u{1f600}", r"\xd83d\xde00"),
+            //an octal digit after a control character forces 3 digits
+            ("\u{1}7", r"\0017"),
+            ("\u{1}8", r"\18"),
+            ("\u{0}", r"\0"),
+            ("\u{1b}[", r"\e["),
+            ("a\\b\"c", r#"a\\b\"c"#),
+        ];
+
+        for (text, expected) in cases {
+            assert_eq!(escape(text, &[b'"' as u16]), expected);
+            let quoted = format!("{}\"", expected);
+            assert_eq!(unescape_until(&quoted, '"').unwrap().0, text);
+        }
+
+        assert_eq!(escape("[a]", &[b'[' as u16, b']' as u16]), r"\[a\]");
+    }
+
+    #[test]
+    fn wraps_long_hex_and_reads_it_back() {
+        let mut hive = parse_registry(SYSTEM_REG).unwrap();
+        let data: Vec<u8> = (0..=255).collect();
+        hive.set_value("Software\\Test", "Blob", RegValue::Binary(data.clone()));
+        hive.set_value(
+            "Software\\Test",
+            "Raw",
+            RegValue::Raw {
+                kind: 0x20,
+                data: data.clone(),
+            },
+        );
+
+        let output = serialize_registry(&hive);
+        assert!(output
+            .lines()
+            .filter(|line| line.contains("hex") || line.starts_with("  "))
+            .all(|line| line.len() <= HEX_LINE_WIDTH + 4));
+        assert!(output.contains("\"Blob\"=hex:00,01,02"));
+
+        let reparsed = parse_registry(&output).unwrap();
+        assert_eq!(
+            reparsed.get_value("Software\\Test", "Blob"),
+            Some(&RegValue::Binary(data.clone()))
+        );
+        assert_eq!(
+            reparsed.get_value("Software\\Test", "Raw"),
+            Some(&RegValue::Raw { kind: 0x20, data })
+        );
+        assert_eq!(serialize_registry(&reparsed), output);
+    }
+
+    #[test]
+    fn strings_ending_in_a_backslash_are_not_continued() {
+        let content = "WINE REGISTRY Version 2\n\n[Software\\\\Test] 1\n\"Path\"=\"C:\\\\games=hex\\\\\"\n\"Next\"=dword:00000002\n";
+        let hive = parse_registry(content).unwrap();
+
+        assert_eq!(
+            hive.get_string(r"Software\Test", "Path"),
+            Some(r"C:\games=hex\")
+        );
+        assert_eq!(hive.get_dword(r"Software\Test", "Next"), Some(2));
+        assert_eq!(serialize_registry(&hive), content);
+
+        //lines that end in a backslash without being wrapped hex
+        assert!(continues_on_next_line(r#""Blob"=hex:00,01,\"#));
+        assert!(continues_on_next_line(r#"@=hex(2):25,00,\"#));
+        assert!(!continues_on_next_line(r#""Cmd"="run =hex\"#));
+        assert!(!continues_on_next_line(r#""a=hex"=str(2):"C:\"#));
+    }
+
+    #[test]
+    fn reads_regedit_import_files() {
+        let content = "Windows Registry Editor Version 5.00\r\n\r\n[HKEY_CURRENT_USER\\Software\\Wine\\DllOverrides]\r\n\"dinput8\"=\"native,builtin\"\r\n\"winemenubuilder.exe\"=\"\"\r\n\r\n[HKEY_LOCAL_MACHINE\\Software\\Test]\r\n\"Flag\"=dword:00000001\r\n\"Blob\"=hex:01,\\\r\n  02\r\n\"Gone\"=-\r\n\r\n[HKEY_CLASSES_ROOT\\.exe]\r\n@=\"exefile\"\r\n";
+
+        let path = std::env::temp_dir().join("sbrw-regedit-test.reg");
+        let utf16: Vec<u8> = [0xff, 0xfe]
+            .into_iter()
+            .chain(content.encode_utf16().flat_map(u16::to_le_bytes))
+            .collect();
+        fs::write(&path, utf16).unwrap();
+        let entries = read_regedit_file(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(entries, parse_regedit_file(content));
+        assert_eq!(
+            entries,
+            vec![
+                (
+                    RegistryFile::User,
+                    r"Software\Wine\DllOverrides".to_string(),
+                    "dinput8".to_string(),
+                    RegValue::String("native,builtin".to_string())
+                ),
+                (
+                    RegistryFile::User,
+                    r"Software\Wine\DllOverrides".to_string(),
+                    "winemenubuilder.exe".to_string(),
+                    RegValue::String(String::new())
+                ),
+                (
+                    RegistryFile::System,
+                    r"Software\Test".to_string(),
+                    "Flag".to_string(),
+                    RegValue::Dword(1)
+                ),
+                (
+                    RegistryFile::System,
+                    r"Software\Test".to_string(),
+                    "Blob".to_string(),
+                    RegValue::Binary(vec![1, 2])
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,778 @@
+use std::{
+    collections::HashMap,
+    fs,
+    io::Read,
+    os::unix::ffi::OsStrExt,
+    path::{Component, Path, PathBuf},
+    process::Command,
+    sync::{Mutex, PoisonError},
+    time::SystemTime,
+};
+
+use log::{info, warn};
+use once_cell::sync::Lazy;
+use which::which;
+
+use crate::{
+    types::{
+        archive::{ExtractOptions, ExtractProgress},
+        config::{RunnerVersion, RunnersComponent},
+        download::DownloadProgress,
+        error::CustomError,
+        runner::{RunnerArchiveInfo, RunnerKind, RunnerOrigin, RunnerRelease, RunnerSource},
+    },
+    utils::{
+        archive::{archive_base_name, extract_archive, scan_archive},
+        checksum::sha256_bytes,
+        downloader::{download_file, fetch_published_checksum, fetch_releases, Asset, HttpClient},
+        fs::cleanup_file,
+    },
+};
+
+//version sources are tiny text files, anything bigger is not one of them
+const VERSION_FILE_LIMIT: u64 = 64 * 1024;
+const VERSION_FILE: &str = "version";
+//wine compiles the build id "wine --version" prints into ntdll, or libwine before wine 6,
+//relative to the folder holding bin/
+const BUILD_ID_SOURCES: [&str; 6] = [
+    "lib/wine/x86_64-unix/ntdll.so",
+    "lib64/wine/x86_64-unix/ntdll.so",
+    "lib/wine/i386-unix/ntdll.so",
+    "lib64/libwine.so.1",
+    "lib/libwine.so.1",
+    "lib32/libwine.so.1",
+];
+const BUILD_ID_FILE_LIMIT: u64 = 64 * 1024 * 1024;
+const BUILD_ID_PREFIX: &str = "wine-";
+//asset names don't always match the folder a runner extracts to, e.g.
+//wine-lutris-GE-Proton8-26-x86_64.tar.xz holds lutris-GE-Proton8-26-x86_64
+const INSTALLED_ASSETS_FILE: &str = "installed_assets.json";
+//distro wine on PATH and the WineHQ packages, which install into /opt
+const SYSTEM_WINE_COMMANDS: [&str; 2] = ["wine", "wine-staging"];
+const SYSTEM_WINE_DIRS: [&str; 3] = ["/opt/wine-staging", "/opt/wine-devel", "/opt/wine-stable"];
+//distros disagree on where wineserver lives when it isn't next to wine
+const WINESERVER_LOCATIONS: [&str; 5] = [
+    "wineserver",
+    "wineserver64",
+    "../lib/wine/wineserver",
+    "../lib/wine/wineserver64",
+    "../lib64/wine/wineserver",
+];
+const PROTON_SCRIPT: &str = "proton";
+//proton always runs the wine prefix found at <compat data>/pfx
+const PROTON_PREFIX_DIR: &str = "pfx";
+
+type WineVersionCache = HashMap<PathBuf, (Option<SystemTime>, Option<String>)>;
+
+//settings list the system runners every time they open, wine --version is only
+//asked again once the binary changed, e.g. after a distro upgrade
+static SYSTEM_WINE_VERSIONS: Lazy<Mutex<WineVersionCache>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+pub fn inspect_runner_archive(file: &Path) -> Result<RunnerArchiveInfo, CustomError> {
+    let mut wine_binaries: Vec<PathBuf> = Vec::new();
+    let mut version_sources: HashMap<PathBuf, String> = HashMap::new();
+
+    scan_archive(file, &mut |path, reader| {
+        if path.file_name().is_some_and(|name| name == "wine")
+            && path.parent().is_some_and(|parent| parent.ends_with("bin"))
+        {
+            wine_binaries.push(path.to_path_buf());
+        }
+
+        if let Some(content) = read_version_source(path, reader) {
+            version_sources.insert(path.to_path_buf(), content);
+        }
+
+        Ok(())
+    })?;
+
+    //the shallowest bin/wine wins, deeper ones are usually bundled helpers
+    let (kind, root) = wine_binaries
+        .iter()
+        .filter_map(|wine_path| runner_root(wine_path))
+        .min_by_key(|(_, root)| root.components().count())
+        .ok_or_else(|| {
+            CustomError::PathError(format!(
+                "No bin/wine or files/bin/wine found in {}",
+                file.display()
+            ))
+        })?;
+
+    let file_name = file
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    //a bare usr/ root comes from distro style packages, the archive name says more there
+    let name = match root.file_name().and_then(|name| name.to_str()) {
+        Some(name) if name != "usr" => name.to_string(),
+        _ => archive_base_name(&file_name).to_string(),
+    };
+
+    let version = read_runner_version(&root, kind, |path| version_sources.get(path).cloned());
+
+    Ok(RunnerArchiveInfo {
+        kind,
+        root,
+        name,
+        version,
+    })
+}
+
+pub fn install_runner_archive(
+    archive_path: &Path,
+    runners_dir: &Path,
+    on_progress: &mut dyn FnMut(ExtractProgress),
+) -> Result<RunnerArchiveInfo, CustomError> {
+    let file_name = archive_path
+        .file_name()
+        .ok_or_else(|| CustomError::PathError("Invalid archive path".to_string()))?
+        .to_string_lossy()
+        .into_owned();
+
+    //the archive contents decide what kind of runner this is and where it starts
+    let runner = inspect_runner_archive(archive_path)?;
+
+    let target_dir = runners_dir
+        .join(runner.kind.runners_dir_name())
+        .join(&runner.name);
+
+    if target_dir.exists() {
+        return Err(CustomError::PathError(format!(
+            "Version already exists at {}",
+            target_dir.display()
+        )));
+    }
+    fs::create_dir_all(&target_dir)?;
+
+    extract_archive(
+        archive_path,
+        &target_dir,
+        &ExtractOptions {
+            root: Some(runner.root.clone()),
+            ..Default::default()
+        },
+        on_progress,
+    )
+    .inspect_err(|_| {
+        //a half extracted runner would otherwise block adding it again
+        fs::remove_dir_all(&target_dir).ok();
+    })?;
+
+    info!(
+        "Added {:?} runner {} ({}) from archive: {}",
+        runner.kind,
+        runner.name,
+        runner.version.as_deref().unwrap_or("unknown version"),
+        file_name
+    );
+
+    Ok(runner)
+}
+
+pub fn list_runner_releases(
+    client: &HttpClient,
+    sources: &[RunnerSource],
+    releases_per_source: u32,
+    runners_dir: &Path,
+) -> Result<Vec<RunnerRelease>, CustomError> {
+    let mut releases = Vec::new();
+    let mut last_error = None;
+    let installed_assets = load_installed_assets(runners_dir);
+
+    for source in sources {
+        //one unreachable or renamed repo shouldn't hide the other sources
+        let source_releases = match fetch_releases(client, &source.repo, releases_per_source) {
+            Ok(source_releases) => source_releases,
+            Err(err) => {
+                warn!("Failed to list releases of {}: {}", source.repo, err);
+                last_error = Some(err);
+                continue;
+            }
+        };
+
+        for release in source_releases {
+            for asset in release
+                .assets
+                .iter()
+                .filter(|asset| asset.name.ends_with(&source.asset_suffix))
+            {
+                let installed = match installed_assets.get(&asset.name) {
+                    Some(runner_dir) => runners_dir.join(runner_dir).is_dir(),
+                    //runners installed before the manifest existed
+                    None => runners_dir
+                        .join(source.kind.runners_dir_name())
+                        .join(archive_base_name(&asset.name))
+                        .is_dir(),
+                };
+
+                releases.push(RunnerRelease {
+                    source: source.name.clone(),
+                    kind: source.kind,
+                    tag_name: release.tag_name.clone(),
+                    asset_name: asset.name.clone(),
+                    download_url: asset.browser_download_url.clone(),
+                    size: asset.size,
+                    digest: asset.digest.clone(),
+                    published_at: release.published_at.clone(),
+                    prerelease: release.prerelease,
+                    installed,
+                });
+            }
+        }
+    }
+
+    //only fail when nothing could be listed at all
+    match last_error {
+        Some(err) if releases.is_empty() => Err(err),
+        _ => Ok(releases),
+    }
+}
+
+fn is_plain_file_name(name: &str) -> bool {
+    let mut components = Path::new(name).components();
+
+    !name.starts_with('.')
+        && matches!(
+            (components.next(), components.next()),
+            (Some(Component::Normal(_)), None)
+        )
+}
+
+//the release comes from the frontend, only an asset its source really lists gets downloaded
+fn find_release_asset(
+    client: &HttpClient,
+    settings: &RunnersComponent,
+    release: &RunnerRelease,
+) -> Result<Asset, CustomError> {
+    let source = settings
+        .sources
+        .iter()
+        .find(|source| source.name == release.source)
+        .ok_or_else(|| {
+            CustomError::PathError(format!("Unknown runner source: {}", release.source))
+        })?;
+
+    fetch_releases(client, &source.repo, settings.releases_per_source)?
+        .into_iter()
+        .filter(|listed| listed.tag_name == release.tag_name)
+        .flat_map(|listed| listed.assets)
+        .find(|asset| {
+            asset.name == release.asset_name
+                && asset.name.ends_with(&source.asset_suffix)
+                && is_plain_file_name(&asset.name)
+        })
+        .ok_or_else(|| {
+            CustomError::PathError(format!(
+                "{} is not an asset of {} {}",
+                release.asset_name, source.repo, release.tag_name
+            ))
+        })
+}
+
+pub fn download_runner(
+    client: &HttpClient,
+    settings: &RunnersComponent,
+    release: &RunnerRelease,
+    runners_dir: &Path,
+    on_download: &mut dyn FnMut(DownloadProgress),
+    on_extract: &mut dyn FnMut(ExtractProgress),
+) -> Result<RunnerArchiveInfo, CustomError> {
+    let asset = find_release_asset(client, settings, release)?;
+
+    //interrupted downloads stay here as .part files and resume on the next try
+    let downloads_dir = runners_dir.join("downloads");
+    fs::create_dir_all(&downloads_dir)?;
+
+    //GitHub's digest comes first, older releases may still publish a checksum file
+    let checksum = match asset.sha256() {
+        Some(checksum) => Some(checksum),
+        None => fetch_published_checksum(client, &asset.browser_download_url, &asset.name)?,
+    };
+
+    let archive_path = download_file(
+        client,
+        &asset.browser_download_url,
+        &downloads_dir,
+        &asset.name,
+        checksum.as_deref(),
+        on_download,
+        None,
+    )?;
+
+    let runner = install_runner_archive(&archive_path, runners_dir, on_extract);
+
+    //the download cache keeps its own copy, this one is only needed for the install
+    cleanup_file(&archive_path)?;
+
+    let runner = runner?;
+    record_installed_asset(runners_dir, &asset.name, &runner)?;
+
+    if runner.kind != release.kind {
+        warn!(
+            "{} was listed as {:?} but contains a {:?} runner",
+            release.asset_name, release.kind, runner.kind
+        );
+    }
+
+    Ok(runner)
+}
+
+fn load_installed_assets(runners_dir: &Path) -> HashMap<String, PathBuf> {
+    fs::read_to_string(runners_dir.join(INSTALLED_ASSETS_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn record_installed_asset(
+    runners_dir: &Path,
+    asset_name: &str,
+    runner: &RunnerArchiveInfo,
+) -> Result<(), CustomError> {
+    let mut installed_assets = load_installed_assets(runners_dir);
+    installed_assets.insert(
+        asset_name.to_string(),
+        Path::new(runner.kind.runners_dir_name()).join(&runner.name),
+    );
+
+    fs::write(
+        runners_dir.join(INSTALLED_ASSETS_FILE),
+        serde_json::to_string_pretty(&installed_assets)?,
+    )?;
+
+    Ok(())
+}
+
+pub fn read_installed_runner_version(runner_dir: &Path, kind: RunnerKind) -> Option<String> {
+    read_runner_version(runner_dir, kind, |path| {
+        read_version_source(path, &mut fs::File::open(path).ok()?)
+    })
+}
+
+//text of a version file, or the build id found in one of wine's libraries
+fn read_version_source(path: &Path, reader: &mut dyn Read) -> Option<String> {
+    if BUILD_ID_SOURCES
+        .iter()
+        .any(|location| path.ends_with(location))
+    {
+        return find_build_id(reader);
+    }
+
+    if !path.ends_with(VERSION_FILE) {
+        return None;
+    }
+
+    let mut content = Vec::new();
+    reader
+        .take(VERSION_FILE_LIMIT)
+        .read_to_end(&mut content)
+        .ok()?;
+
+    Some(String::from_utf8_lossy(&content).into_owned())
+}
+
+//the build id is a plain c string, e.g. "wine-9.0 (Staging)"
+fn find_build_id(reader: &mut dyn Read) -> Option<String> {
+    let mut content = Vec::new();
+    reader
+        .take(BUILD_ID_FILE_LIMIT)
+        .read_to_end(&mut content)
+        .ok()?;
+
+    content.split(|byte| *byte == 0).find_map(|bytes| {
+        let text = std::str::from_utf8(bytes).ok()?;
+        let is_build_id = text.len() <= 64
+            && text
+                .strip_prefix(BUILD_ID_PREFIX)
+                .is_some_and(|version| version.starts_with(|c: char| c.is_ascii_digit()))
+            && text.chars().all(|c| c.is_ascii_graphic() || c == ' ');
+
+        is_build_id.then(|| text.to_string())
+    })
+}
+
+fn runner_root(wine_path: &Path) -> Option<(RunnerKind, PathBuf)> {
+    let bin_dir = wine_path.parent()?;
+    let parent = bin_dir.parent().unwrap_or(Path::new(""));
+
+    if parent.ends_with("files") {
+        Some((
+            RunnerKind::Proton,
+            parent.parent().unwrap_or(Path::new("")).to_path_buf(),
+        ))
+    } else {
+        Some((RunnerKind::Wine, parent.to_path_buf()))
+    }
+}
+
+fn read_runner_version(
+    root: &Path,
+    kind: RunnerKind,
+    read: impl Fn(&Path) -> Option<String>,
+) -> Option<String> {
+    //proton writes "<timestamp> <name>" to its version file, wine builds that ship one
+    //only have the version in it
+    let from_version_file = read(&root.join(VERSION_FILE)).and_then(|content| {
+        let line = content.lines().next()?.trim();
+        let version = match kind {
+            RunnerKind::Wine => line,
+            RunnerKind::Proton => line.split_whitespace().last()?,
+        };
+
+        (!version.is_empty()).then(|| version.to_string())
+    });
+
+    from_version_file.or_else(|| {
+        let data_dir = root.join(kind.bin_dir());
+        let data_dir = data_dir.parent()?;
+
+        BUILD_ID_SOURCES
+            .iter()
+            .find_map(|location| read(&data_dir.join(location)))
+            .map(|build_id| build_id.trim_start_matches(BUILD_ID_PREFIX).to_string())
+    })
+}
+
+pub fn detect_system_runners() -> Vec<RunnerVersion> {
+    let path_candidates = SYSTEM_WINE_COMMANDS
+        .iter()
+        .filter_map(|command| which(command).ok().map(|path| (command.to_string(), path)));
+
+    let opt_candidates = SYSTEM_WINE_DIRS.iter().filter_map(|dir| {
+        let name = Path::new(dir).file_name()?.to_str()?.to_string();
+        Some((name, resolve_wine_binary(&Path::new(dir).join("bin"))))
+    });
+
+    let mut runners: Vec<RunnerVersion> = Vec::new();
+    let mut seen_binaries: Vec<PathBuf> = Vec::new();
+
+    //the binary is kept instead of its folder, /usr/bin may hold wine and wine-staging
+    for (name, binary) in path_candidates.chain(opt_candidates) {
+        //wine on PATH is often a symlink into one of the /opt installs
+        let binary = match binary.canonicalize() {
+            Ok(binary) if binary.is_file() => binary,
+            _ => continue,
+        };
+
+        if seen_binaries.contains(&binary) {
+            continue;
+        }
+
+        let version = cached_wine_version(&binary);
+        if version.is_none() {
+            warn!(
+                "Skipping system wine at {}, it doesn't run",
+                binary.display()
+            );
+            continue;
+        }
+
+        seen_binaries.push(binary.clone());
+        runners.push(RunnerVersion {
+            name: format!("system-{}", name),
+            path: binary.display().to_string(),
+            version,
+            origin: RunnerOrigin::System,
+        });
+    }
+
+    runners
+}
+
+//system runners are configured by their wine binary, the others by their bin folder
+pub fn runner_bin_dir(wine_path: &Path) -> &Path {
+    match wine_path.parent() {
+        Some(parent) if wine_path.is_file() => parent,
+        _ => wine_path,
+    }
+}
+
+pub fn resolve_wine_binary(wine_path: &Path) -> PathBuf {
+    if wine_path.is_file() {
+        return wine_path.to_path_buf();
+    }
+
+    //older 64 bit only distro packages have no plain wine binary
+    let wine64 = wine_path.join("wine64");
+    if !wine_path.join("wine").is_file() && wine64.is_file() {
+        return wine64;
+    }
+
+    wine_path.join("wine")
+}
+
+pub fn resolve_wineserver(wine_path: &Path) -> PathBuf {
+    let wine_dir = runner_bin_dir(wine_path);
+
+    //bundled runners ship wineserver next to wine, distro packages may not
+    WINESERVER_LOCATIONS
+        .iter()
+        .map(|location| wine_dir.join(location))
+        .find(|path| path.is_file())
+        .or_else(|| which("wineserver").ok())
+        .unwrap_or_else(|| wine_dir.join("wineserver"))
+}
+
+pub fn resolve_proton_root(wine_dir: &Path) -> Option<PathBuf> {
+    //proton runners are configured by their files/bin folder, the script sits at the root
+    if !wine_dir.ends_with(RunnerKind::Proton.bin_dir()) {
+        return None;
+    }
+
+    let root = wine_dir.parent()?.parent()?;
+    root.join(PROTON_SCRIPT)
+        .is_file()
+        .then(|| root.to_path_buf())
+}
+
+pub fn resolve_proton_script(proton_root: &Path) -> PathBuf {
+    proton_root.join(PROTON_SCRIPT)
+}
+
+//proton writes its version and tracked files next to pfx, so the prefix gets a compat data
+//folder of its own under compat_root instead of being turned into one
+pub fn prepare_compat_data(compat_root: &Path, wine_prefix: &Path) -> Result<PathBuf, CustomError> {
+    if wine_prefix
+        .file_name()
+        .is_some_and(|name| name == PROTON_PREFIX_DIR)
+    {
+        if let Some(compat_data) = wine_prefix.parent() {
+            return Ok(compat_data.to_path_buf());
+        }
+    }
+
+    //prefixes sharing a folder name in different places get different compat data
+    let prefix_name = wine_prefix
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let path_hash = sha256_bytes(wine_prefix.as_os_str().as_bytes());
+    let compat_data = compat_root.join(format!("{}-{}", prefix_name, &path_hash[..12]));
+
+    fs::create_dir_all(wine_prefix)?;
+    fs::create_dir_all(&compat_data)?;
+
+    let pfx = compat_data.join(PROTON_PREFIX_DIR);
+    if fs::symlink_metadata(&pfx).is_err() {
+        std::os::unix::fs::symlink(wine_prefix, &pfx)?;
+    }
+
+    //earlier versions linked pfx back to the prefix inside the prefix itself
+    let self_link = wine_prefix.join(PROTON_PREFIX_DIR);
+    if fs::read_link(&self_link).is_ok_and(|target| target == Path::new(".")) {
+        fs::remove_file(&self_link)?;
+    }
+
+    Ok(compat_data)
+}
+
+fn cached_wine_version(wine_binary: &Path) -> Option<String> {
+    let modified = fs::metadata(wine_binary)
+        .and_then(|metadata| metadata.modified())
+        .ok();
+    let mut versions = SYSTEM_WINE_VERSIONS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+
+    if let Some((cached_modified, version)) = versions.get(wine_binary) {
+        if *cached_modified == modified {
+            return version.clone();
+        }
+    }
+
+    let version = wine_version_output(wine_binary);
+    versions.insert(wine_binary.to_path_buf(), (modified, version.clone()));
+
+    version
+}
+
+fn wine_version_output(wine_binary: &Path) -> Option<String> {
+    let output = Command::new(wine_binary).arg("--version").output().ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    //prints e.g. "wine-9.0 (Staging)"
+    let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+    Some(
+        version
+            .strip_prefix("wine-")
+            .unwrap_or(&version)
+            .to_string(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        types::config::NetworkComponent,
+        utils::{
+            checksum::sha256_bytes,
+            downloader::build_client,
+            test_server::{header, request_path, response, start_server, temp_dir},
+        },
+    };
+
+    const REPO: &str = "GloriousEggroll/wine-ge-custom";
+    const ASSET_NAME: &str = "wine-lutris-GE-Proton8-26-x86_64.tar.xz";
+    const ARCHIVE_ROOT: &str = "lutris-GE-Proton8-26-x86_64";
+
+    //laid out like a Wine-GE release, whose top level folder isn't named after the asset
+    fn runner_archive() -> Vec<u8> {
+        let mut builder = tar::Builder::new(xz2::write::XzEncoder::new(Vec::new(), 6));
+
+        for (path, mode, content) in [
+            //the version must come from ntdll, running the binary would fail
+            ("bin/wine", 0o755, "#!/bin/sh\nexit 1\n"),
+            (
+                "lib/wine/x86_64-unix/ntdll.so",
+                0o644,
+                "\x7fELF\0wine_get_build_id\0wine-8.0-GE-1\0",
+            ),
+        ] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(mode);
+            builder
+                .append_data(
+                    &mut header,
+                    format!("{}/{}", ARCHIVE_ROOT, path),
+                    content.as_bytes(),
+                )
+                .unwrap();
+        }
+
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    //serves the releases api and the asset download, nothing publishes a checksum
+    fn start_release_server() -> String {
+        let archive = runner_archive();
+
+        let (base_url, _) = start_server(move |_, request| {
+            let path = request_path(request);
+            let host = header(request, "host").unwrap_or_default();
+
+            if path.starts_with(&format!("/repos/{}/releases", REPO)) {
+                let releases = serde_json::json!([{
+                    "tag_name": "GE-Proton8-26",
+                    "assets": [{
+                        "name": ASSET_NAME,
+                        "browser_download_url": format!("http://{}/download/{}", host, ASSET_NAME),
+                        "size": archive.len(),
+                        "digest": format!("sha256:{}", sha256_bytes(&archive)),
+                    }],
+                }]);
+                response("200 OK", releases.to_string().as_bytes())
+            } else if path == format!("/download/{}", ASSET_NAME) {
+                response("200 OK", &archive)
+            } else {
+                response("404 Not Found", b"")
+            }
+        });
+
+        base_url
+    }
+
+    fn runner_settings() -> RunnersComponent {
+        RunnersComponent {
+            sources: vec![RunnerSource {
+                name: "Wine-GE".to_string(),
+                repo: REPO.to_string(),
+                kind: RunnerKind::Wine,
+                asset_suffix: ".tar.xz".to_string(),
+            }],
+            ..RunnersComponent::default()
+        }
+    }
+
+    #[test]
+    fn refuses_assets_the_release_does_not_list() {
+        let dir = temp_dir("runner-assets");
+        let runners_dir = dir.join("runners");
+        let network = NetworkComponent {
+            api_base_url: start_release_server(),
+            ..NetworkComponent::default()
+        };
+        let client = build_client(&network, &dir.join("cache")).unwrap();
+        let settings = runner_settings();
+
+        let mut release = list_runner_releases(&client, &settings.sources, 5, &runners_dir)
+            .unwrap()
+            .remove(0);
+        release.asset_name = format!("../../{}", ASSET_NAME);
+
+        let result = download_runner(
+            &client,
+            &settings,
+            &release,
+            &runners_dir,
+            &mut |_| {},
+            &mut |_| {},
+        );
+
+        assert!(matches!(result, Err(CustomError::PathError(_))));
+        assert!(!dir.join(ASSET_NAME).exists());
+        assert!(!runners_dir.join("downloads").exists());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn installs_releases_from_a_mock_release_server() {
+        let dir = temp_dir("runner-releases");
+        let runners_dir = dir.join("runners");
+        let network = NetworkComponent {
+            api_base_url: start_release_server(),
+            ..NetworkComponent::default()
+        };
+        let client = build_client(&network, &dir.join("cache")).unwrap();
+        let settings = runner_settings();
+
+        let releases = list_runner_releases(&client, &settings.sources, 5, &runners_dir).unwrap();
+        assert_eq!(releases.len(), 1);
+        assert_eq!(releases[0].asset_name, ASSET_NAME);
+        assert!(releases[0].digest.is_some());
+        assert!(!releases[0].installed);
+
+        let mut downloaded_bytes = 0;
+        let runner = download_runner(
+            &client,
+            &settings,
+            &releases[0],
+            &runners_dir,
+            &mut |progress| downloaded_bytes = progress.downloaded_bytes,
+            &mut |_| {},
+        )
+        .unwrap();
+
+        assert_eq!(downloaded_bytes, releases[0].size);
+        assert_eq!(runner.kind, RunnerKind::Wine);
+        assert_eq!(runner.name, ARCHIVE_ROOT);
+        assert_eq!(runner.version.as_deref(), Some("8.0-GE-1"));
+        assert!(runners_dir
+            .join("wine")
+            .join(ARCHIVE_ROOT)
+            .join("bin/wine")
+            .is_file());
+
+        //the folder differs from the asset name, the manifest still matches them up
+        let releases = list_runner_releases(&client, &settings.sources, 5, &runners_dir).unwrap();
+        assert!(releases[0].installed);
+        assert_eq!(
+            read_installed_runner_version(
+                &runners_dir.join("wine").join(ARCHIVE_ROOT),
+                runner.kind
+            )
+            .as_deref(),
+            Some("8.0-GE-1")
+        );
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
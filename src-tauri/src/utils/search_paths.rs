@@ -0,0 +1,123 @@
+use std::path::{Path, PathBuf};
+
+use crate::types::{config::SearchPathsComponent, runner::RunnerOrigin};
+
+//folders other launchers install their runners into, relative to the home directory.
+//the .var/app ones are the flatpak builds of the same launchers
+const RUNNER_LOCATIONS: [(RunnerOrigin, &str); 11] = [
+    (RunnerOrigin::Lutris, ".local/share/lutris/runners/wine"),
+    (
+        RunnerOrigin::Lutris,
+        ".var/app/net.lutris.Lutris/data/lutris/runners/wine",
+    ),
+    (RunnerOrigin::Steam, ".steam/root/compatibilitytools.d"),
+    (
+        RunnerOrigin::Steam,
+        ".local/share/Steam/compatibilitytools.d",
+    ),
+    (
+        RunnerOrigin::Steam,
+        ".var/app/com.valvesoftware.Steam/.local/share/Steam/compatibilitytools.d",
+    ),
+    (RunnerOrigin::Bottles, ".local/share/bottles/runners"),
+    (
+        RunnerOrigin::Bottles,
+        ".var/app/com.usebottles.bottles/data/bottles/runners",
+    ),
+    (RunnerOrigin::Heroic, ".config/heroic/tools/wine"),
+    (RunnerOrigin::Heroic, ".config/heroic/tools/proton"),
+    (
+        RunnerOrigin::Heroic,
+        ".var/app/com.heroicgameslauncher.hgl/config/heroic/tools/wine",
+    ),
+    (
+        RunnerOrigin::Heroic,
+        ".var/app/com.heroicgameslauncher.hgl/config/heroic/tools/proton",
+    ),
+];
+
+const DXVK_LOCATIONS: [(RunnerOrigin, &str); 6] = [
+    (RunnerOrigin::Lutris, ".local/share/lutris/runtime/dxvk"),
+    (
+        RunnerOrigin::Lutris,
+        ".var/app/net.lutris.Lutris/data/lutris/runtime/dxvk",
+    ),
+    (RunnerOrigin::Bottles, ".local/share/bottles/dxvk"),
+    (
+        RunnerOrigin::Bottles,
+        ".var/app/com.usebottles.bottles/data/bottles/dxvk",
+    ),
+    (RunnerOrigin::Heroic, ".config/heroic/tools/dxvk"),
+    (
+        RunnerOrigin::Heroic,
+        ".var/app/com.heroicgameslauncher.hgl/config/heroic/tools/dxvk",
+    ),
+];
+
+pub fn runner_search_paths(
+    home_dir: &Path,
+    settings: &SearchPathsComponent,
+) -> Vec<(PathBuf, RunnerOrigin)> {
+    search_paths(
+        home_dir,
+        settings.auto_detect,
+        &RUNNER_LOCATIONS,
+        &settings.runner_paths,
+    )
+}
+
+pub fn dxvk_search_paths(
+    home_dir: &Path,
+    settings: &SearchPathsComponent,
+) -> Vec<(PathBuf, RunnerOrigin)> {
+    search_paths(
+        home_dir,
+        settings.auto_detect,
+        &DXVK_LOCATIONS,
+        &settings.dxvk_paths,
+    )
+}
+
+fn search_paths(
+    home_dir: &Path,
+    auto_detect: bool,
+    locations: &[(RunnerOrigin, &str)],
+    custom_paths: &[String],
+) -> Vec<(PathBuf, RunnerOrigin)> {
+    let detected = locations
+        .iter()
+        .filter(|_| auto_detect)
+        .map(|(origin, location)| (home_dir.join(location), *origin));
+
+    let custom = custom_paths
+        .iter()
+        .filter(|path| !path.trim().is_empty())
+        .map(|path| (expand_home(home_dir, path.trim()), RunnerOrigin::Custom));
+
+    let mut paths: Vec<(PathBuf, RunnerOrigin)> = Vec::new();
+    let mut seen: Vec<PathBuf> = Vec::new();
+
+    //~/.steam/root is usually a symlink to ~/.local/share/Steam, list each folder once
+    for (path, origin) in detected.chain(custom) {
+        let canonical_path = match path.canonicalize() {
+            Ok(canonical_path) if canonical_path.is_dir() => canonical_path,
+            _ => continue,
+        };
+
+        if seen.contains(&canonical_path) {
+            continue;
+        }
+
+        seen.push(canonical_path);
+        paths.push((path, origin));
+    }
+
+    paths
+}
+
+fn expand_home(home_dir: &Path, path: &str) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(relative) => home_dir.join(relative),
+        None => PathBuf::from(path),
+    }
+}
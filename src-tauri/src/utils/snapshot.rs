@@ -0,0 +1,245 @@
+use std::{
+    cmp::Reverse,
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use log::{info, warn};
+
+use crate::{
+    types::{
+        archive::{ExtractOptions, ExtractProgress},
+        config::Config,
+        error::CustomError,
+        snapshot::PrefixSnapshot,
+    },
+    utils::{
+        archive::{create_tar_zst, extract_archive},
+        fs::{cleanup_dir, cleanup_file},
+    },
+};
+
+const ARCHIVE_EXTENSION: &str = "tar.zst";
+const METADATA_EXTENSION: &str = "json";
+//the launcher and the game it downloads, several GB that don't change with the prefix
+const GAME_DIR: &str = "drive_c/SBRW";
+
+//ids come from the frontend and end up in file names
+fn check_snapshot_id(id: &str) -> Result<(), CustomError> {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_digit()) {
+        return Err(CustomError::PathError(format!(
+            "Invalid snapshot id: {}",
+            id
+        )));
+    }
+
+    Ok(())
+}
+
+fn archive_path(snapshots_dir: &Path, id: &str) -> PathBuf {
+    snapshots_dir.join(format!("{}.{}", id, ARCHIVE_EXTENSION))
+}
+
+fn metadata_path(snapshots_dir: &Path, id: &str) -> PathBuf {
+    snapshots_dir.join(format!("{}.{}", id, METADATA_EXTENSION))
+}
+
+fn read_snapshot(snapshots_dir: &Path, id: &str) -> Result<PrefixSnapshot, CustomError> {
+    check_snapshot_id(id)?;
+
+    let metadata_path = metadata_path(snapshots_dir, id);
+    if !metadata_path.is_file() || !archive_path(snapshots_dir, id).is_file() {
+        return Err(CustomError::PathError(format!("Snapshot {} not found", id)));
+    }
+
+    Ok(serde_json::from_str(&fs::read_to_string(metadata_path)?)?)
+}
+
+//the prefix has to be released before this, wineserver writes the registry files on exit
+pub fn create_snapshot(
+    snapshots_dir: &Path,
+    config: &Config,
+    include_game_files: bool,
+) -> Result<PrefixSnapshot, CustomError> {
+    let wine_prefix = Path::new(&config.game.prefix);
+    if !wine_prefix.join("system.reg").is_file() {
+        return Err(CustomError::PathError(format!(
+            "No wine prefix found at {}",
+            wine_prefix.display()
+        )));
+    }
+
+    fs::create_dir_all(snapshots_dir)?;
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let id = now.as_millis().to_string();
+    let archive_path = archive_path(snapshots_dir, &id);
+    let partial_path = snapshots_dir.join(format!("{}.{}.part", id, ARCHIVE_EXTENSION));
+
+    let excluded: Vec<&Path> = if include_game_files {
+        Vec::new()
+    } else {
+        vec![Path::new(GAME_DIR)]
+    };
+
+    //a cancelled or failed snapshot never shows up in the list
+    create_tar_zst(wine_prefix, &partial_path, &excluded).inspect_err(|_| {
+        cleanup_file(&partial_path).ok();
+    })?;
+    fs::rename(&partial_path, &archive_path)?;
+
+    let snapshot = PrefixSnapshot {
+        id: id.clone(),
+        created_at: now.as_secs(),
+        prefix: config.game.prefix.clone(),
+        runner: config.wine.version.clone(),
+        runner_path: config.wine.path.clone(),
+        dxvk_version: config.dxvk.version.clone(),
+        dxvk_enabled: config.dxvk.enabled,
+        d3d9_backend: config.d3d9.backend,
+        include_game_files,
+        size: fs::metadata(&archive_path)?.len(),
+    };
+
+    fs::write(
+        metadata_path(snapshots_dir, &id),
+        serde_json::to_string_pretty(&snapshot)?,
+    )?;
+
+    info!(
+        "Created prefix snapshot {} ({} bytes)",
+        snapshot.id, snapshot.size
+    );
+
+    Ok(snapshot)
+}
+
+//newest first
+pub fn list_snapshots(snapshots_dir: &Path) -> Result<Vec<PrefixSnapshot>, CustomError> {
+    if !snapshots_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut snapshots = Vec::new();
+
+    for entry in fs::read_dir(snapshots_dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some(METADATA_EXTENSION) {
+            continue;
+        }
+
+        let snapshot: PrefixSnapshot = match fs::read_to_string(&path)
+            .map_err(CustomError::from)
+            .and_then(|content| Ok(serde_json::from_str(&content)?))
+        {
+            Ok(snapshot) => snapshot,
+            Err(err) => {
+                warn!("Skipping unreadable snapshot {}: {}", path.display(), err);
+                continue;
+            }
+        };
+
+        if !archive_path(snapshots_dir, &snapshot.id).is_file() {
+            warn!("Skipping snapshot {}, its archive is missing", snapshot.id);
+            continue;
+        }
+
+        snapshots.push(snapshot);
+    }
+
+    snapshots.sort_by_key(|snapshot| Reverse(snapshot.created_at));
+
+    Ok(snapshots)
+}
+
+//moves the replacement in place of the target, putting the target back if that fails
+fn swap_dirs(target: &Path, replacement: &Path, backup: &Path) -> Result<(), CustomError> {
+    if target.exists() {
+        fs::rename(target, backup)?;
+    }
+
+    if let Err(err) = fs::rename(replacement, target) {
+        if backup.exists() {
+            fs::rename(backup, target).ok();
+        }
+        return Err(err.into());
+    }
+
+    Ok(())
+}
+
+//the prefix has to be released before this, same as for create_snapshot
+pub fn restore_snapshot(
+    snapshots_dir: &Path,
+    id: &str,
+    wine_prefix: &Path,
+    on_progress: &mut dyn FnMut(ExtractProgress),
+) -> Result<PrefixSnapshot, CustomError> {
+    let snapshot = read_snapshot(snapshots_dir, id)?;
+
+    let (parent_dir, prefix_name) = match (wine_prefix.parent(), wine_prefix.file_name()) {
+        (Some(parent_dir), Some(prefix_name)) => (parent_dir, prefix_name.to_string_lossy()),
+        _ => {
+            return Err(CustomError::PathError(format!(
+                "Invalid wine prefix path: {}",
+                wine_prefix.display()
+            )))
+        }
+    };
+
+    //extracting next to the prefix keeps the final swap a rename on the same filesystem
+    let restore_dir = parent_dir.join(format!(".{}.restore", prefix_name));
+    let backup_dir = parent_dir.join(format!(".{}.old", prefix_name));
+    cleanup_dir(&restore_dir, "leftover snapshot restore")?;
+    cleanup_dir(&backup_dir, "leftover prefix backup")?;
+
+    extract_archive(
+        &archive_path(snapshots_dir, id),
+        &restore_dir,
+        &ExtractOptions::default(),
+        on_progress,
+    )
+    .inspect_err(|_| {
+        cleanup_dir(&restore_dir, "incomplete snapshot restore").ok();
+    })?;
+
+    //snapshots without the game files keep the ones already installed
+    let game_dir = wine_prefix.join(GAME_DIR);
+    let restored_game_dir = restore_dir.join(GAME_DIR);
+    let keep_game_files = !snapshot.include_game_files && game_dir.is_dir();
+    if keep_game_files {
+        fs::rename(&game_dir, &restored_game_dir)?;
+    }
+
+    if let Err(err) = swap_dirs(wine_prefix, &restore_dir, &backup_dir) {
+        if keep_game_files {
+            fs::rename(&restored_game_dir, &game_dir).ok();
+        }
+        cleanup_dir(&restore_dir, "incomplete snapshot restore").ok();
+        return Err(err);
+    }
+
+    cleanup_dir(&backup_dir, "previous wine prefix")?;
+
+    info!(
+        "Restored prefix snapshot {} to {}",
+        snapshot.id,
+        wine_prefix.display()
+    );
+
+    Ok(snapshot)
+}
+
+pub fn delete_snapshot(snapshots_dir: &Path, id: &str) -> Result<(), CustomError> {
+    check_snapshot_id(id)?;
+
+    cleanup_file(&archive_path(snapshots_dir, id))?;
+    cleanup_file(&metadata_path(snapshots_dir, id))?;
+
+    info!("Deleted prefix snapshot {}", id);
+
+    Ok(())
+}
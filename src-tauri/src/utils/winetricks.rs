@@ -0,0 +1,174 @@
+use std::{collections::HashSet, fs, path::Path, process::Command};
+
+use anyhow::anyhow;
+use log::info;
+use tauri::Window;
+
+use crate::{
+    setup::wine::stop_wine_prefix,
+    types::{error::CustomError, winetricks::WinetricksVerb},
+    utils::{
+        cancel::CancellationToken,
+        emitter::report_event,
+        process::run_command_streamed,
+        runner::{resolve_wine_binary, resolve_wineserver},
+    },
+};
+
+const WINETRICKS: &str = "winetricks";
+//winetricks appends every verb it finished to this file in the prefix
+const WINETRICKS_LOG: &str = "winetricks.log";
+const DOWNLOAD_MARKERS: [&str; 2] = ["[downloadable]", "[cached]"];
+
+fn path_to_string(path: &Path) -> Result<String, CustomError> {
+    path.to_str()
+        .map(str::to_string)
+        .ok_or_else(|| CustomError::PathError(format!("Invalid path {:?}", path)))
+}
+
+//the runner picked in the settings instead of whatever wine is on PATH
+fn winetricks_envs(
+    wine_prefix: &Path,
+    wine_dir: &Path,
+) -> Result<Vec<(String, String)>, CustomError> {
+    Ok(vec![
+        ("WINEPREFIX".to_string(), path_to_string(wine_prefix)?),
+        (
+            "WINE".to_string(),
+            path_to_string(&resolve_wine_binary(wine_dir))?,
+        ),
+        (
+            "WINESERVER".to_string(),
+            path_to_string(&resolve_wineserver(wine_dir))?,
+        ),
+        (
+            "WINEDLLOVERRIDES".to_string(),
+            "winemenubuilder=".to_string(),
+        ),
+        (
+            "WINETRICKS_LATEST_VERSION_CHECK".to_string(),
+            "disabled".to_string(),
+        ),
+    ])
+}
+
+pub fn read_installed_verbs(wine_prefix: &Path) -> Vec<String> {
+    let log = fs::read_to_string(wine_prefix.join(WINETRICKS_LOG)).unwrap_or_default();
+    let mut seen = HashSet::new();
+
+    log.lines()
+        .map(str::trim)
+        .filter(|verb| !verb.is_empty() && seen.insert(*verb))
+        .map(str::to_string)
+        .collect()
+}
+
+pub fn list_winetricks_verbs(
+    wine_prefix: &Path,
+    wine_dir: &Path,
+) -> Result<Vec<WinetricksVerb>, CustomError> {
+    let output = Command::new(WINETRICKS)
+        .arg("list-all")
+        .envs(winetricks_envs(wine_prefix, wine_dir)?)
+        .output()?;
+
+    if !output.status.success() {
+        return Err(CustomError::Anyhow(anyhow!(
+            "winetricks list-all failed with status: {:?}",
+            output.status
+        )));
+    }
+
+    let installed = read_installed_verbs(wine_prefix);
+    let mut category = String::new();
+    let mut verbs: Vec<WinetricksVerb> = Vec::new();
+
+    //sections start with "===== dlls =====", then one "verb  description" per line
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if let Some(section) = line
+            .trim()
+            .strip_prefix("=====")
+            .and_then(|rest| rest.strip_suffix("====="))
+        {
+            category = section.trim().to_string();
+            continue;
+        }
+
+        let Some((name, description)) = line.trim().split_once(char::is_whitespace) else {
+            continue;
+        };
+
+        let description = DOWNLOAD_MARKERS
+            .iter()
+            .fold(description.trim(), |text, marker| {
+                text.trim_end_matches(marker).trim_end()
+            });
+
+        verbs.push(WinetricksVerb {
+            name: name.to_string(),
+            category: category.clone(),
+            description: description.to_string(),
+            installed: installed.iter().any(|verb| verb == name),
+        });
+    }
+
+    Ok(verbs)
+}
+
+pub fn run_winetricks(
+    verbs: &[String],
+    wine_prefix: &Path,
+    wine_dir: &Path,
+    window: &Window,
+    token: &CancellationToken,
+) -> Result<(), CustomError> {
+    //verbs are passed as arguments, anything looking like an option could change what runs
+    if let Some(verb) = verbs.iter().find(|verb| {
+        verb.is_empty()
+            || verb.starts_with('-')
+            || !verb
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "_=.-".contains(c))
+    }) {
+        return Err(CustomError::Anyhow(anyhow!(
+            "Invalid winetricks verb: {}",
+            verb
+        )));
+    }
+
+    let envs = winetricks_envs(wine_prefix, wine_dir)?;
+    let envs: Vec<(&str, &str)> = envs
+        .iter()
+        .map(|(key, value)| (key.as_str(), value.as_str()))
+        .collect();
+
+    let mut args: Vec<&str> = vec!["--unattended"];
+    args.extend(verbs.iter().map(String::as_str));
+
+    info!(
+        "Running winetricks {} in {}",
+        verbs.join(" "),
+        wine_prefix.display()
+    );
+
+    let result = run_command_streamed(
+        WINETRICKS,
+        &args,
+        &envs,
+        &mut |line| {
+            report_event(window, "winetricks-output", line).ok();
+        },
+        Some(token),
+    );
+
+    //killing winetricks leaves the installers it started running in the prefix
+    if let Err(CustomError::Cancelled) = result {
+        info!("winetricks cancelled, stopping the prefix");
+        stop_wine_prefix(&resolve_wineserver(wine_dir), &path_to_string(wine_prefix)?)?;
+    }
+    result?;
+
+    info!("winetricks finished: {}", verbs.join(" "));
+
+    Ok(())
+}
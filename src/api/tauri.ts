@@ -5,12 +5,19 @@ export const startSettingUp = async (options: any): Promise<string> => {
     return await invoke<string>("setup_environment", {
         options: {
             wine_prefix: options.winePrefix,
+            resume: options.resumeChecked,
+            network: options.network,
             // desktop_shortcut_needed: options.desktopShorcutChecked,
             // menu_shortcut_needed: options.menuShortcutChecked,
         },
     });
 };
 
+//cancel running setup
+export const cancelSetup = async () => {
+    return await invoke<void>("cancel_setup");
+};
+
 //check config.toml whether prompt user to install or launch game
 export const checkConfig = async () => {
     return await invoke<boolean>("check_config");
@@ -49,3 +56,108 @@ export const addWineVersion = async (selectedPath: string) => {
         selectedPath,
     });
 };
+
+//list downloadable wine/proton runners
+export const getRunnerReleases = async () => {
+    return await invoke<IRunnerRelease[]>("get_runner_releases");
+};
+
+//download and install a runner release
+export const installRunner = async (release: IRunnerRelease) => {
+    return await invoke<IVersions>("install_runner", {
+        release,
+    });
+};
+
+//list downloadable dxvk builds
+export const getDXVKReleases = async () => {
+    return await invoke<ICatalogRelease[]>("get_dxvk_releases");
+};
+
+//download and install a dxvk release
+export const installDXVK = async (release: ICatalogRelease) => {
+    return await invoke<IVersions>("install_dxvk", {
+        release,
+    });
+};
+
+//remove an installed wine/proton version, returns the bytes reclaimed
+export const removeWineVersion = async (name: string) => {
+    return await invoke<number>("remove_wine_version", {
+        name,
+    });
+};
+
+//remove an installed dxvk version, returns the bytes reclaimed
+export const removeDXVKVersion = async (name: string) => {
+    return await invoke<number>("remove_dxvk_version", {
+        name,
+    });
+};
+
+//dxvk build currently linked into the prefix
+export const getPrefixDXVKState = async () => {
+    return await invoke<IPrefixDXVKState>("get_prefix_dxvk_state");
+};
+
+//remove dxvk links from the prefix and restore wine's own dlls
+export const removeDXVKFromPrefix = async () => {
+    return await invoke<IPrefixDXVKState>("remove_dxvk_from_prefix");
+};
+
+//winecfg settings read from the prefix' registry
+export const getPrefixSettings = async () => {
+    return await invoke<IPrefixSettings>("get_prefix_settings");
+};
+
+//write winecfg settings into the prefix' registry, stops its wineserver first
+export const setPrefixSettings = async (settings: IPrefixSettings) => {
+    return await invoke<IPrefixSettings>("set_prefix_settings", { settings });
+};
+
+//verbs winetricks offers, marked when already installed in the prefix
+export const getWinetricksVerbs = async () => {
+    return await invoke<IWinetricksVerb[]>("get_winetricks_verbs");
+};
+
+//run winetricks verbs in the prefix, output arrives as winetricks-output events
+export const installWinetricksVerbs = async (verbs: string[]) => {
+    return await invoke<string[]>("install_winetricks_verbs", { verbs });
+};
+
+//kill a running winetricks install and the prefix' wine processes
+export const cancelWinetricks = async () => {
+    return await invoke<void>("cancel_winetricks");
+};
+
+//archive the prefix, stops its wineserver first
+export const createPrefixSnapshot = async (includeGameFiles: boolean) => {
+    return await invoke<IPrefixSnapshot>("create_prefix_snapshot", {
+        includeGameFiles,
+    });
+};
+
+//snapshots taken of the prefix, newest first
+export const listPrefixSnapshots = async () => {
+    return await invoke<IPrefixSnapshot[]>("list_prefix_snapshots");
+};
+
+//replace the prefix with a snapshot, progress arrives as extract-progress events
+export const restorePrefixSnapshot = async (id: string) => {
+    return await invoke<IPrefixSnapshot>("restore_prefix_snapshot", { id });
+};
+
+//delete a snapshot and its metadata
+export const deletePrefixSnapshot = async (id: string) => {
+    return await invoke<void>("delete_prefix_snapshot", { id });
+};
+
+//get download cache contents
+export const getDownloadCache = async () => {
+    return await invoke<ICacheInfo>("get_download_cache");
+};
+
+//clear download cache
+export const clearDownloadCache = async () => {
+    return await invoke<void>("clear_download_cache");
+};
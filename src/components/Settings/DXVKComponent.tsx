@@ -1,23 +1,104 @@
+import { ask, message } from "@tauri-apps/plugin-dialog";
 import Select from "../../layouts/Select/Select";
 import Toggle from "../../layouts/Toggle/Toggle";
+import { useEffect, useState } from "react";
+import {
+    getPrefixDXVKState,
+    removeDXVKFromPrefix,
+    removeDXVKVersion,
+} from "../../api/tauri";
 
 interface IDXVK {
     dxvk: DXVKSettings;
+    d3d9: D3D9Settings;
     availableCommands: IAvailableCommands;
     onChange: (dxvk: DXVKSettings) => void;
+    onD3D9Change: (d3d9: D3D9Settings) => void;
     availableDXVKVersions: IVersions[];
+    onVersionRemoved: (name: string) => void;
 }
 
 const DXVKComponent = ({
     dxvk,
+    d3d9,
     availableCommands,
     onChange,
+    onD3D9Change,
     availableDXVKVersions,
+    onVersionRemoved,
 }: IDXVK) => {
+    const [prefixState, setPrefixState] = useState<IPrefixDXVKState | null>(
+        null,
+    );
+
+    useEffect(() => {
+        getPrefixDXVKState()
+            .then(setPrefixState)
+            .catch(() => setPrefixState(null));
+    }, []);
+
+    const handleRemoveFromPrefix = async () => {
+        const confirmed = await ask(
+            "Remove DXVK from the prefix and restore Wine's own DLLs?",
+            {
+                title: "Remove DXVK from prefix",
+                kind: "warning",
+            },
+        );
+        if (!confirmed) {
+            return;
+        }
+
+        try {
+            setPrefixState(await removeDXVKFromPrefix());
+            onChange({ ...dxvk, enabled: false });
+        } catch (error) {
+            await message(String(error), {
+                title: "Something Went Wrong",
+                kind: "error",
+            });
+        }
+    };
+
     const handleDXVKVersionChange = (version: string) => {
         let path = availableDXVKVersions.find((v) => v.name === version)?.path;
         onChange({ ...dxvk, version: version, path: path as string });
     };
+
+    const handleRemoveDXVKVersion = async () => {
+        const confirmed = await ask(`Remove ${dxvk.version}?`, {
+            title: "Remove DXVK version",
+            kind: "warning",
+        });
+        if (!confirmed) {
+            return;
+        }
+
+        try {
+            const reclaimed = await removeDXVKVersion(dxvk.version);
+            onVersionRemoved(dxvk.version);
+
+            const remaining = availableDXVKVersions.filter(
+                (v) => v.name !== dxvk.version,
+            );
+            if (remaining.length > 0) {
+                handleDXVKVersionChange(remaining[0].name);
+            }
+
+            await message(
+                `Removed ${dxvk.version}, freed ${(reclaimed / (1024 * 1024)).toFixed(0)} MB`,
+                {
+                    title: "Success",
+                    kind: "info",
+                },
+            );
+        } catch (error) {
+            await message(String(error), {
+                title: "Something Went Wrong",
+                kind: "error",
+            });
+        }
+    };
     return (
         <div className="flex flex-col gap-y-3 pl-6">
             <h2 className="text-black dark:text-white font-bold -ml-6">
@@ -29,16 +110,81 @@ const DXVKComponent = ({
                 checked={dxvk.enabled}
                 onChange={(enabled) => onChange({ ...dxvk, enabled })}
             />
-            <Select
-                id="dxvk-version"
-                label="DXVK version"
-                value={dxvk.version}
-                onChange={(version) => handleDXVKVersionChange(version)}
-                options={availableDXVKVersions.map((v) => ({
-                    label: v.name,
-                    value: v.name,
-                }))}
+            <div className="flex items-center gap-x-4">
+                <Select
+                    id="dxvk-version"
+                    label="DXVK version"
+                    value={dxvk.version}
+                    onChange={(version) => handleDXVKVersionChange(version)}
+                    options={availableDXVKVersions.map((v) => ({
+                        label:
+                            v.origin && v.origin !== "Utility"
+                                ? `${v.name} (${v.origin})`
+                                : v.name,
+                        value: v.name,
+                    }))}
+                />
+                <button
+                    onClick={handleRemoveDXVKVersion}
+                    disabled={
+                        availableDXVKVersions.find(
+                            (v) => v.name === dxvk.version,
+                        )?.origin !== "Utility"
+                    }
+                    className="bg-white dark:bg-[#444] w-fit h-full p-2 rounded cursor-pointer disabled:pointer-events-none disabled:opacity-50"
+                >
+                    <svg
+                        xmlns="http://www.w3.org/2000/svg"
+                        fill="none"
+                        viewBox="0 0 24 24"
+                        stroke-width="1.5"
+                        stroke="currentColor"
+                        className="size-5 text-black dark:text-white"
+                    >
+                        <path
+                            stroke-linecap="round"
+                            stroke-linejoin="round"
+                            d="m14.74 9-.346 9m-4.788 0L9.26 9m9.968-3.21c.342.052.682.107 1.022.166m-1.022-.165L18.16 19.673a2.25 2.25 0 0 1-2.244 2.077H8.084a2.25 2.25 0 0 1-2.244-2.077L4.772 5.79m14.456 0a48.108 48.108 0 0 0-3.478-.397m-12 .562c.34-.059.68-.114 1.022-.165m0 0a48.11 48.11 0 0 1 3.478-.397m7.5 0v-.916c0-1.18-.91-2.164-2.09-2.201a51.964 51.964 0 0 0-3.32 0c-1.18.037-2.09 1.022-2.09 2.201v.916m7.5 0a48.667 48.667 0 0 0-7.5 0"
+                        />
+                    </svg>
+                </button>
+            </div>
+            <Select<DXVKInstallMode>
+                id="dxvk-install-mode"
+                label="Install mode"
+                value={dxvk.install_mode}
+                onChange={(install_mode) => onChange({ ...dxvk, install_mode })}
+                options={[
+                    { label: "Symlink", value: "symlink" },
+                    { label: "Copy", value: "copy" },
+                ]}
+            />
+            <Select<D3D9Backend>
+                id="d3d9-backend"
+                label="Direct3D 9 backend"
+                value={d3d9.backend}
+                onChange={(backend) => onD3D9Change({ ...d3d9, backend })}
+                options={[
+                    { label: "WineD3D", value: "wined3d" },
+                    { label: "DXVK", value: "dxvk" },
+                    { label: "Gallium Nine", value: "nine" },
+                ]}
             />
+            <div className="flex items-center gap-x-4 text-black dark:text-white text-[15px]">
+                <span>
+                    In prefix:{" "}
+                    {prefixState?.installed
+                        ? (prefixState.version ?? "unknown version")
+                        : "Wine DLLs"}
+                </span>
+                <button
+                    onClick={handleRemoveFromPrefix}
+                    disabled={!prefixState?.installed}
+                    className="bg-white dark:bg-[#444] w-fit px-3 py-1 rounded cursor-pointer disabled:pointer-events-none disabled:opacity-50"
+                >
+                    Remove from prefix
+                </button>
+            </div>
         </div>
     );
 };
@@ -0,0 +1,127 @@
+import { message } from "@tauri-apps/plugin-dialog";
+import { useEffect, useState } from "react";
+import Select from "../../layouts/Select/Select";
+import TextField from "../../layouts/TextField/TextField";
+import Toggle from "../../layouts/Toggle/Toggle";
+import { getPrefixSettings, setPrefixSettings } from "../../api/tauri";
+
+//written straight into the prefix' registry, separate from the utility's own config
+const PrefixComponent = () => {
+    const [prefixSettings, setPrefixSettingsState] =
+        useState<IPrefixSettings | null>(null);
+    const [applying, setApplying] = useState<boolean>(false);
+
+    useEffect(() => {
+        getPrefixSettings()
+            .then(setPrefixSettingsState)
+            .catch(() => setPrefixSettingsState(null));
+    }, []);
+
+    if (!prefixSettings) {
+        return null;
+    }
+
+    const handleChange = (changes: Partial<IPrefixSettings>) => {
+        setPrefixSettingsState({ ...prefixSettings, ...changes });
+    };
+
+    const handleApply = async () => {
+        setApplying(true);
+        try {
+            setPrefixSettingsState(await setPrefixSettings(prefixSettings));
+        } catch (error) {
+            await message(String(error), {
+                title: "Something Went Wrong",
+                kind: "error",
+            });
+        } finally {
+            setApplying(false);
+        }
+    };
+
+    return (
+        <div className="flex flex-col gap-y-3 pl-6">
+            <h2 className="text-black dark:text-white font-bold -ml-6">
+                Prefix Settings
+            </h2>
+            <Select<WindowsVersion>
+                id="windows-version"
+                label="Windows version"
+                value={prefixSettings.windows_version}
+                onChange={(windows_version) => handleChange({ windows_version })}
+                options={[
+                    { label: "Windows 11", value: "win11" },
+                    { label: "Windows 10", value: "win10" },
+                    { label: "Windows 8.1", value: "win81" },
+                    { label: "Windows 8", value: "win8" },
+                    { label: "Windows 7", value: "win7" },
+                    { label: "Windows Vista", value: "vista" },
+                    { label: "Windows XP", value: "winxp" },
+                ]}
+            />
+            <Toggle
+                id="virtual-desktop"
+                label="Emulate a virtual desktop"
+                checked={prefixSettings.virtual_desktop}
+                onChange={(virtual_desktop) => handleChange({ virtual_desktop })}
+            />
+            {prefixSettings.virtual_desktop && (
+                <>
+                    <TextField
+                        id="desktop-width"
+                        label="Desktop width"
+                        type="number"
+                        value={String(prefixSettings.desktop_width)}
+                        onChange={(val) =>
+                            handleChange({ desktop_width: Number(val) })
+                        }
+                    />
+                    <TextField
+                        id="desktop-height"
+                        label="Desktop height"
+                        type="number"
+                        value={String(prefixSettings.desktop_height)}
+                        onChange={(val) =>
+                            handleChange({ desktop_height: Number(val) })
+                        }
+                    />
+                </>
+            )}
+            <Select<AudioDriver>
+                id="audio-driver"
+                label="Audio driver"
+                value={prefixSettings.audio_driver}
+                onChange={(audio_driver) => handleChange({ audio_driver })}
+                options={[
+                    { label: "Automatic", value: "auto" },
+                    { label: "PulseAudio", value: "pulse" },
+                    { label: "ALSA", value: "alsa" },
+                    { label: "OSS", value: "oss" },
+                    { label: "Disabled", value: "disabled" },
+                ]}
+            />
+            <Select<MouseWarpOverride>
+                id="mouse-warp-override"
+                label="Mouse warp override"
+                value={prefixSettings.mouse_warp_override}
+                onChange={(mouse_warp_override) =>
+                    handleChange({ mouse_warp_override })
+                }
+                options={[
+                    { label: "Enable", value: "enable" },
+                    { label: "Disable", value: "disable" },
+                    { label: "Force", value: "force" },
+                ]}
+            />
+            <button
+                onClick={handleApply}
+                disabled={applying}
+                className="bg-white dark:bg-[#444] text-black dark:text-white w-fit px-3 py-1 rounded cursor-pointer disabled:pointer-events-none disabled:opacity-50"
+            >
+                Apply to prefix
+            </button>
+        </div>
+    );
+};
+
+export default PrefixComponent;
@@ -0,0 +1,158 @@
+import { useEffect, useState } from "react";
+import { listen } from "@tauri-apps/api/event";
+import { message } from "@tauri-apps/plugin-dialog";
+
+interface IRunnerManager<T extends ICatalogRelease> {
+    title: string;
+    browseLabel: string;
+    fetchReleases: () => Promise<T[]>;
+    installRelease: (release: T) => Promise<IVersions>;
+    onInstalled: (version: IVersions) => void;
+}
+
+const toMegabytes = (bytes: number) => (bytes / (1024 * 1024)).toFixed(0);
+
+const RunnerManagerComponent = <T extends ICatalogRelease>({
+    title,
+    browseLabel,
+    fetchReleases,
+    installRelease,
+    onInstalled,
+}: IRunnerManager<T>) => {
+    const [releases, setReleases] = useState<T[]>([]);
+    const [loading, setLoading] = useState<boolean>(false);
+    const [installing, setInstalling] = useState<string | null>(null);
+    const [status, setStatus] = useState<string>("");
+
+    useEffect(() => {
+        let unlistenDownload: (() => void) | null = null;
+        let unlistenExtract: (() => void) | null = null;
+
+        const setupListeners = async () => {
+            unlistenDownload = await listen<IDownloadProgress>(
+                "download-progress",
+                (event) => {
+                    const { downloaded_bytes, total_bytes } = event.payload;
+                    const total =
+                        total_bytes !== null
+                            ? ` / ${toMegabytes(total_bytes)}`
+                            : "";
+                    setStatus(
+                        `Downloading... ${toMegabytes(downloaded_bytes)}${total} MB`,
+                    );
+                },
+            );
+
+            unlistenExtract = await listen<IExtractProgress>(
+                "extract-progress",
+                (event) => {
+                    setStatus(
+                        `Extracting... ${event.payload.entries_extracted} files`,
+                    );
+                },
+            );
+        };
+
+        setupListeners();
+
+        return () => {
+            if (unlistenDownload) unlistenDownload();
+            if (unlistenExtract) unlistenExtract();
+        };
+    }, []);
+
+    const handleLoadReleases = async () => {
+        setLoading(true);
+        try {
+            setReleases(await fetchReleases());
+        } catch (error) {
+            await message(String(error), {
+                title: "Something Went Wrong",
+                kind: "error",
+            });
+        } finally {
+            setLoading(false);
+        }
+    };
+
+    const handleInstall = async (release: T) => {
+        setInstalling(release.asset_name);
+        setStatus("");
+        try {
+            const version = await installRelease(release);
+            onInstalled(version);
+            setReleases((current) =>
+                current.map((r) =>
+                    r.asset_name === release.asset_name
+                        ? { ...r, installed: true }
+                        : r,
+                ),
+            );
+            await message(`${version.name} installed`, {
+                title: "Success",
+                kind: "info",
+            });
+        } catch (error) {
+            await message(String(error), {
+                title: "Something Went Wrong",
+                kind: "error",
+            });
+        } finally {
+            setInstalling(null);
+            setStatus("");
+        }
+    };
+
+    return (
+        <div className="flex flex-col gap-y-3 pl-6">
+            <h2 className="text-black dark:text-white font-bold -ml-6">
+                {title}
+            </h2>
+            <div className="flex items-center gap-x-4">
+                <button
+                    className="bg-white dark:bg-[#444] text-black dark:text-white w-fit px-3 py-[6px] rounded cursor-pointer disabled:pointer-events-none disabled:opacity-50"
+                    onClick={handleLoadReleases}
+                    disabled={loading || installing !== null}
+                >
+                    {loading ? "Loading..." : browseLabel}
+                </button>
+                {status && (
+                    <span className="text-black dark:text-white text-[15px]">
+                        {status}
+                    </span>
+                )}
+            </div>
+            {releases.length > 0 && (
+                <div className="flex flex-col gap-y-2 max-h-72 overflow-y-auto">
+                    {releases.map((release) => (
+                        <div
+                            key={`${release.source}-${release.asset_name}`}
+                            className="flex items-center justify-between text-black dark:text-white text-[15px]"
+                        >
+                            <span>
+                                {release.source} · {release.asset_name}
+                                {release.size > 0 &&
+                                    ` (${toMegabytes(release.size)} MB)`}
+                            </span>
+                            <button
+                                className="bg-white dark:bg-[#444] w-fit px-3 py-1 rounded cursor-pointer disabled:pointer-events-none disabled:opacity-50"
+                                onClick={() => handleInstall(release)}
+                                disabled={
+                                    release.installed || installing !== null
+                                }
+                            >
+                                {release.installed
+                                    ? "Installed"
+                                    : installing === release.asset_name
+                                      ? "Installing..."
+                                      : "Install"}
+                            </button>
+                        </div>
+                    ))}
+                </div>
+            )}
+        </div>
+    );
+};
+
+export default RunnerManagerComponent;
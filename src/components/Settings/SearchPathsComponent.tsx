@@ -0,0 +1,48 @@
+import TextField from "../../layouts/TextField/TextField";
+import Toggle from "../../layouts/Toggle/Toggle";
+
+interface ISearchPaths {
+    searchPaths: SearchPathsSettings;
+    onChange: (searchPaths: SearchPathsSettings) => void;
+}
+
+// paths are edited like $PATH, separated by ":"
+const toPathList = (value: string) => value.split(":");
+
+const SearchPathsComponent = ({ searchPaths, onChange }: ISearchPaths) => {
+    return (
+        <div className="flex flex-col gap-y-3 pl-6">
+            <h2 className="text-black dark:text-white font-bold -ml-6">
+                Search Paths
+            </h2>
+            <Toggle
+                id="auto-detect-runners"
+                label="Detect Lutris, Steam, Bottles and Heroic runners"
+                checked={searchPaths.auto_detect}
+                onChange={(auto_detect) =>
+                    onChange({ ...searchPaths, auto_detect })
+                }
+            />
+            <TextField
+                id="runner-search-paths"
+                label="Extra runner paths"
+                type="text"
+                value={searchPaths.runner_paths.join(":")}
+                onChange={(val) =>
+                    onChange({ ...searchPaths, runner_paths: toPathList(val) })
+                }
+            />
+            <TextField
+                id="dxvk-search-paths"
+                label="Extra DXVK paths"
+                type="text"
+                value={searchPaths.dxvk_paths.join(":")}
+                onChange={(val) =>
+                    onChange({ ...searchPaths, dxvk_paths: toPathList(val) })
+                }
+            />
+        </div>
+    );
+};
+
+export default SearchPathsComponent;
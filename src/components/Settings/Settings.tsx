@@ -1,14 +1,24 @@
 import { useEffect, useState } from "react";
 import {
     getAvailableCommands,
+    getDXVKReleases,
+    getRunnerReleases,
+    installDXVK,
+    installRunner,
     populateSettings,
     saveSettings,
 } from "../../api/tauri";
 import { Link, useNavigate } from "react-router-dom";
+import { message } from "@tauri-apps/plugin-dialog";
 import GameComponent from "./GameComponent";
 import DXVKComponent from "./DXVKComponent";
 import WineComponent from "./WineComponent";
 import VariablesComponent from "./VariablesComponent";
+import RunnerManagerComponent from "./RunnerManagerComponent";
+import SearchPathsComponent from "./SearchPathsComponent";
+import PrefixComponent from "./PrefixComponent";
+import WinetricksComponent from "./WinetricksComponent";
+import SnapshotsComponent from "./SnapshotsComponent";
 
 const Settings = () => {
     const navigate = useNavigate();
@@ -27,6 +37,13 @@ const Settings = () => {
         version: "",
         path: "",
         enabled: false,
+        install_mode: "symlink",
+    });
+
+    const [d3d9State, setD3D9State] = useState<D3D9Settings>({
+        backend: "dxvk",
+        nine_version: "",
+        nine_path: "",
     });
 
     const [gameState, setGameState] = useState<GameSettings>({
@@ -36,8 +53,21 @@ const Settings = () => {
         mangohud: false,
         environment_variables: [],
         dll_overrides: [],
+        persist_dll_overrides: false,
     });
 
+    const [searchPathsState, setSearchPathsState] =
+        useState<SearchPathsSettings>({
+            auto_detect: true,
+            runner_paths: [],
+            dxvk_paths: [],
+        });
+
+    //config sections without an editor here, sent back untouched on save
+    const [otherSettings, setOtherSettings] = useState<
+        Partial<IPopulateSettings>
+    >({});
+
     const [availableWineVersions, setAvailableWineVersions] = useState<
         IVersions[]
     >([]);
@@ -53,6 +83,7 @@ const Settings = () => {
 
     const handleSettingsSave = async () => {
         const config = {
+            ...otherSettings,
             wine: {
                 version: wineState.version,
                 path: wineState.path,
@@ -64,7 +95,9 @@ const Settings = () => {
                 version: dxvkState.version,
                 path: dxvkState.path,
                 enabled: dxvkState.enabled,
+                install_mode: dxvkState.install_mode,
             },
+            d3d9: d3d9State,
             game: {
                 prefix: gameState.prefix,
                 directory: gameState.directory,
@@ -72,13 +105,31 @@ const Settings = () => {
                 mangohud: gameState.mangohud,
                 environment_variables: gameState.environment_variables,
                 dll_overrides: gameState.dll_overrides,
+                persist_dll_overrides: gameState.persist_dll_overrides,
+            },
+            search_paths: {
+                auto_detect: searchPathsState.auto_detect,
+                runner_paths: searchPathsState.runner_paths.filter(
+                    (p) => p.trim() !== "",
+                ),
+                dxvk_paths: searchPathsState.dxvk_paths.filter(
+                    (p) => p.trim() !== "",
+                ),
             },
         };
-        await saveSettings(config).then((response) => {
-            if (response) {
-                navigate("/");
-            }
-        });
+        //switching dxvk happens on save and can fail, e.g. for a broken build
+        await saveSettings(config)
+            .then((response) => {
+                if (response) {
+                    navigate("/");
+                }
+            })
+            .catch(async (error) => {
+                await message(String(error), {
+                    title: "Something Went Wrong",
+                    kind: "error",
+                });
+            });
     };
 
     useEffect(() => {
@@ -88,9 +139,22 @@ const Settings = () => {
                     populateSettings(),
                     getAvailableCommands(),
                 ]);
-                setWineState(config.wine);
-                setDXVKState(config.dxvk);
-                setGameState(config.game);
+                const {
+                    wine,
+                    dxvk,
+                    game,
+                    available_wine_versions,
+                    available_dxvk_versions,
+                    search_paths,
+                    d3d9,
+                    ...rest
+                } = config;
+                setWineState(wine);
+                setDXVKState(dxvk);
+                setGameState(game);
+                if (search_paths) setSearchPathsState(search_paths);
+                if (d3d9) setD3D9State(d3d9);
+                setOtherSettings(rest);
                 setAvailableWineVersions(config.available_wine_versions || []);
                 setAvailableDXVKVersions(config.available_dxvk_versions || []);
                 setAvailableCommands(availability);
@@ -117,12 +181,57 @@ const Settings = () => {
                             availableCommands={availableCommands}
                             onChange={setWineState}
                             availableWineVersions={availableWineVersions}
+                            onVersionRemoved={(name) =>
+                                setAvailableWineVersions((current) =>
+                                    current.filter((v) => v.name !== name),
+                                )
+                            }
+                        />
+                        <RunnerManagerComponent
+                            title="Runner Manager"
+                            browseLabel="Browse runners"
+                            fetchReleases={getRunnerReleases}
+                            installRelease={installRunner}
+                            onInstalled={(version) =>
+                                setAvailableWineVersions((current) => [
+                                    ...current,
+                                    version,
+                                ])
+                            }
                         />
                         <DXVKComponent
                             dxvk={dxvkState}
+                            d3d9={d3d9State}
                             availableCommands={availableCommands}
                             onChange={setDXVKState}
+                            onD3D9Change={setD3D9State}
                             availableDXVKVersions={availableDXVKVersions}
+                            onVersionRemoved={(name) =>
+                                setAvailableDXVKVersions((current) =>
+                                    current.filter((v) => v.name !== name),
+                                )
+                            }
+                        />
+                        <RunnerManagerComponent
+                            title="DXVK Manager"
+                            browseLabel="Browse DXVK"
+                            fetchReleases={getDXVKReleases}
+                            installRelease={installDXVK}
+                            onInstalled={(version) =>
+                                setAvailableDXVKVersions((current) => [
+                                    ...current,
+                                    version,
+                                ])
+                            }
+                        />
+                        <PrefixComponent />
+                        <WinetricksComponent
+                            availableCommands={availableCommands}
+                        />
+                        <SnapshotsComponent />
+                        <SearchPathsComponent
+                            searchPaths={searchPathsState}
+                            onChange={setSearchPathsState}
                         />
                         <VariablesComponent
                             game={gameState}
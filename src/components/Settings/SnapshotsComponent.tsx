@@ -0,0 +1,183 @@
+import { listen } from "@tauri-apps/api/event";
+import { ask, message } from "@tauri-apps/plugin-dialog";
+import { useEffect, useState } from "react";
+import Toggle from "../../layouts/Toggle/Toggle";
+import {
+    createPrefixSnapshot,
+    deletePrefixSnapshot,
+    listPrefixSnapshots,
+    restorePrefixSnapshot,
+} from "../../api/tauri";
+
+const toMegabytes = (bytes: number) => (bytes / (1024 * 1024)).toFixed(0);
+
+const describeSnapshot = (snapshot: IPrefixSnapshot) => {
+    const dxvk = snapshot.dxvk_enabled
+        ? `DXVK ${snapshot.dxvk_version}`
+        : "no DXVK";
+    const game = snapshot.include_game_files ? ", with game files" : "";
+    return `${snapshot.runner}, ${dxvk}, d3d9 ${snapshot.d3d9_backend}${game}`;
+};
+
+const SnapshotsComponent = () => {
+    const [snapshots, setSnapshots] = useState<IPrefixSnapshot[]>([]);
+    const [includeGameFiles, setIncludeGameFiles] = useState<boolean>(false);
+    const [busy, setBusy] = useState<string | null>(null);
+    const [status, setStatus] = useState<string>("");
+
+    useEffect(() => {
+        listPrefixSnapshots()
+            .then(setSnapshots)
+            .catch(() => setSnapshots([]));
+
+        let unlisten: (() => void) | null = null;
+
+        const setupListener = async () => {
+            unlisten = await listen<IExtractProgress>(
+                "extract-progress",
+                (event) => {
+                    setStatus(
+                        `Restoring... ${event.payload.entries_extracted} files`,
+                    );
+                },
+            );
+        };
+
+        setupListener();
+
+        return () => {
+            if (unlisten) unlisten();
+        };
+    }, []);
+
+    const handleError = async (error: unknown) => {
+        await message(String(error), {
+            title: "Something Went Wrong",
+            kind: "error",
+        });
+    };
+
+    const handleCreate = async () => {
+        setBusy("create");
+        setStatus("Creating snapshot...");
+        try {
+            const snapshot = await createPrefixSnapshot(includeGameFiles);
+            setSnapshots((current) => [snapshot, ...current]);
+        } catch (error) {
+            await handleError(error);
+        } finally {
+            setBusy(null);
+            setStatus("");
+        }
+    };
+
+    const handleRestore = async (snapshot: IPrefixSnapshot) => {
+        const confirmed = await ask(
+            `Replace the current prefix with the snapshot from ${new Date(snapshot.created_at * 1000).toLocaleString()}? The game will be closed.`,
+            {
+                title: "Restore prefix snapshot",
+                kind: "warning",
+            },
+        );
+        if (!confirmed) {
+            return;
+        }
+
+        setBusy(snapshot.id);
+        setStatus("Restoring...");
+        try {
+            await restorePrefixSnapshot(snapshot.id);
+            await message("Prefix restored", {
+                title: "Success",
+                kind: "info",
+            });
+        } catch (error) {
+            await handleError(error);
+        } finally {
+            setBusy(null);
+            setStatus("");
+        }
+    };
+
+    const handleDelete = async (snapshot: IPrefixSnapshot) => {
+        const confirmed = await ask("Delete this snapshot?", {
+            title: "Delete prefix snapshot",
+            kind: "warning",
+        });
+        if (!confirmed) {
+            return;
+        }
+
+        try {
+            await deletePrefixSnapshot(snapshot.id);
+            setSnapshots((current) =>
+                current.filter((s) => s.id !== snapshot.id),
+            );
+        } catch (error) {
+            await handleError(error);
+        }
+    };
+
+    return (
+        <div className="flex flex-col gap-y-3 pl-6">
+            <h2 className="text-black dark:text-white font-bold -ml-6">
+                Prefix Snapshots
+            </h2>
+            <Toggle
+                id="snapshot-include-game-files"
+                label="Include game files"
+                checked={includeGameFiles}
+                onChange={setIncludeGameFiles}
+                disabled={busy !== null}
+            />
+            <div className="flex items-center gap-x-4">
+                <button
+                    onClick={handleCreate}
+                    disabled={busy !== null}
+                    className="bg-white dark:bg-[#444] text-black dark:text-white w-fit px-3 py-1 rounded cursor-pointer disabled:pointer-events-none disabled:opacity-50"
+                >
+                    Create snapshot
+                </button>
+                {status && (
+                    <span className="text-black dark:text-white text-[15px]">
+                        {status}
+                    </span>
+                )}
+            </div>
+            {snapshots.map((snapshot) => (
+                <div
+                    key={snapshot.id}
+                    className="flex items-center gap-x-3 text-black dark:text-white text-[15px]"
+                >
+                    <div className="flex flex-col">
+                        <span className="font-medium">
+                            {new Date(
+                                snapshot.created_at * 1000,
+                            ).toLocaleString()}{" "}
+                            ({toMegabytes(snapshot.size)} MB)
+                        </span>
+                        <span className="opacity-70">
+                            {describeSnapshot(snapshot)}
+                        </span>
+                    </div>
+                    <button
+                        onClick={() => handleRestore(snapshot)}
+                        disabled={busy !== null}
+                        className="ml-auto bg-white dark:bg-[#444] text-black dark:text-white w-fit px-3 py-1 rounded cursor-pointer disabled:pointer-events-none disabled:opacity-50"
+                    >
+                        Restore
+                    </button>
+                    <button
+                        onClick={() => handleDelete(snapshot)}
+                        disabled={busy !== null}
+                        className="bg-white dark:bg-[#444] text-black dark:text-white w-fit px-3 py-1 rounded cursor-pointer disabled:pointer-events-none disabled:opacity-50"
+                    >
+                        Delete
+                    </button>
+                </div>
+            ))}
+        </div>
+    );
+};
+
+export default SnapshotsComponent;
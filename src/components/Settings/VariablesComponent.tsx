@@ -1,4 +1,5 @@
 import KeyValueEditor from "../../layouts/KeyValueEditor/KeyValueEditor";
+import Toggle from "../../layouts/Toggle/Toggle";
 
 interface IVariables {
     game: GameSettings;
@@ -29,11 +30,21 @@ const VariablesComponent = ({
                     items={game.environment_variables}
                     onChange={(env_arr) => handleEnvChange(env_arr)}
                 />
-                <KeyValueEditor
-                    label="DLL Overrides"
-                    items={game.dll_overrides}
-                    onChange={(dll_arr) => handleDllChange(dll_arr)}
-                />
+                <div className="flex flex-col gap-y-3">
+                    <KeyValueEditor
+                        label="DLL Overrides"
+                        items={game.dll_overrides}
+                        onChange={(dll_arr) => handleDllChange(dll_arr)}
+                    />
+                    <Toggle
+                        id="persist-dll-overrides"
+                        label="Also write DLL overrides into the prefix registry"
+                        checked={game.persist_dll_overrides}
+                        onChange={(persist_dll_overrides) =>
+                            onChange({ ...game, persist_dll_overrides })
+                        }
+                    />
+                </div>
             </div>
         </div>
     );
@@ -1,8 +1,8 @@
-import { message, open } from "@tauri-apps/plugin-dialog";
+import { ask, message, open } from "@tauri-apps/plugin-dialog";
 import Select from "../../layouts/Select/Select";
 import Toggle from "../../layouts/Toggle/Toggle";
 import { homeDir } from "@tauri-apps/api/path";
-import { addWineVersion } from "../../api/tauri";
+import { addWineVersion, removeWineVersion } from "../../api/tauri";
 import { path } from "@tauri-apps/api";
 
 interface IWine {
@@ -10,19 +10,65 @@ interface IWine {
     availableCommands: IAvailableCommands;
     onChange: (wine: WineSettings) => void;
     availableWineVersions: IVersions[];
+    onVersionRemoved: (name: string) => void;
 }
 
+const versionLabel = (v: IVersions) => {
+    const details = [
+        v.version && !v.name.includes(v.version) ? v.version : null,
+        v.origin && v.origin !== "Utility" ? v.origin : null,
+    ].filter(Boolean);
+
+    return details.length > 0 ? `${v.name} (${details.join(", ")})` : v.name;
+};
+
 const WineComponent = ({
     wine,
     availableCommands,
     onChange,
     availableWineVersions,
+    onVersionRemoved,
 }: IWine) => {
     const handleWineVersionChange = (version: string) => {
         let path = availableWineVersions.find((v) => v.name === version)?.path;
         onChange({ ...wine, version: version, path: path as string });
     };
 
+    const handleRemoveWineVersion = async () => {
+        const confirmed = await ask(`Remove ${wine.version}?`, {
+            title: "Remove wine/proton version",
+            kind: "warning",
+        });
+        if (!confirmed) {
+            return;
+        }
+
+        try {
+            const reclaimed = await removeWineVersion(wine.version);
+            onVersionRemoved(wine.version);
+
+            const remaining = availableWineVersions.filter(
+                (v) => v.name !== wine.version,
+            );
+            if (remaining.length > 0) {
+                handleWineVersionChange(remaining[0].name);
+            }
+
+            await message(
+                `Removed ${wine.version}, freed ${(reclaimed / (1024 * 1024)).toFixed(0)} MB`,
+                {
+                    title: "Success",
+                    kind: "info",
+                },
+            );
+        } catch (error) {
+            await message(String(error), {
+                title: "Something Went Wrong",
+                kind: "error",
+            });
+        }
+    };
+
     const handleAddWineVersion = async () => {
         try {
             const selectedPath = await open({
@@ -38,7 +84,7 @@ const WineComponent = ({
             const fileName = await path.basename(selectedPath);
 
             const strippedName = fileName.replace(
-                /(\.tar\.xz|\.tar\.gz)$/i,
+                /(\.tar\.(gz|xz|bz2|zst|lz4)|\.tgz|\.txz|\.tbz2|\.tzst|\.tar)$/i,
                 "",
             );
 
@@ -53,26 +99,13 @@ const WineComponent = ({
                 return;
             }
 
-            const hasValidExtension =
-                fileName.endsWith(".tar.xz") || fileName.endsWith(".tar.gz");
-            const hasValidPrefix =
-                strippedName.startsWith("wine-") ||
-                strippedName.startsWith("Proton-") ||
-                strippedName.startsWith("GE-Proton");
-
-            if (hasValidExtension && hasValidPrefix) {
-                await addWineVersion(selectedPath).then(() => {
-                    message("wine/proton archive added", {
-                        title: "Success",
-                        kind: "info",
-                    });
-                });
-            } else {
-                await message("Invalid wine/proton archive", {
-                    title: "Invalid",
-                    kind: "error",
+            // the backend inspects the archive contents to find the runner
+            await addWineVersion(selectedPath).then(() => {
+                message("wine/proton archive added", {
+                    title: "Success",
+                    kind: "info",
                 });
-            }
+            });
         } catch (error) {
             await message(String(error), {
                 title: "Something Went Wrong",
@@ -94,7 +127,7 @@ const WineComponent = ({
                         value={wine.version}
                         onChange={(version) => handleWineVersionChange(version)}
                         options={availableWineVersions.map((v) => ({
-                            label: v.name,
+                            label: versionLabel(v),
                             value: v.name,
                         }))}
                     />
@@ -117,6 +150,30 @@ const WineComponent = ({
                             />
                         </svg>
                     </button>
+                    <button
+                        onClick={handleRemoveWineVersion}
+                        disabled={
+                            availableWineVersions.find(
+                                (v) => v.name === wine.version,
+                            )?.origin !== "Utility"
+                        }
+                        className="bg-white dark:bg-[#444] w-fit h-full p-2 rounded cursor-pointer disabled:pointer-events-none disabled:opacity-50"
+                    >
+                        <svg
+                            xmlns="http://www.w3.org/2000/svg"
+                            fill="none"
+                            viewBox="0 0 24 24"
+                            stroke-width="1.5"
+                            stroke="currentColor"
+                            className="size-5 text-black dark:text-white"
+                        >
+                            <path
+                                stroke-linecap="round"
+                                stroke-linejoin="round"
+                                d="m14.74 9-.346 9m-4.788 0L9.26 9m9.968-3.21c.342.052.682.107 1.022.166m-1.022-.165L18.16 19.673a2.25 2.25 0 0 1-2.244 2.077H8.084a2.25 2.25 0 0 1-2.244-2.077L4.772 5.79m14.456 0a48.108 48.108 0 0 0-3.478-.397m-12 .562c.34-.059.68-.114 1.022-.165m0 0a48.11 48.11 0 0 1 3.478-.397m7.5 0v-.916c0-1.18-.91-2.164-2.09-2.201a51.964 51.964 0 0 0-3.32 0c-1.18.037-2.09 1.022-2.09 2.201v.916m7.5 0a48.667 48.667 0 0 0-7.5 0"
+                            />
+                        </svg>
+                    </button>
                 </div>
                 <Toggle
                     id="enable-esync"
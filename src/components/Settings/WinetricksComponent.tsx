@@ -0,0 +1,210 @@
+import { listen } from "@tauri-apps/api/event";
+import { message } from "@tauri-apps/plugin-dialog";
+import { useEffect, useRef, useState } from "react";
+import Select from "../../layouts/Select/Select";
+import TextField from "../../layouts/TextField/TextField";
+import {
+    cancelWinetricks,
+    getWinetricksVerbs,
+    installWinetricksVerbs,
+} from "../../api/tauri";
+
+interface IWinetricks {
+    availableCommands: IAvailableCommands;
+}
+
+//keeps the log view from growing without bound on long installs like dotnet48
+const MAX_OUTPUT_LINES = 500;
+
+const WinetricksComponent = ({ availableCommands }: IWinetricks) => {
+    const [verbs, setVerbs] = useState<IWinetricksVerb[]>([]);
+    const [category, setCategory] = useState<string>("dlls");
+    const [filter, setFilter] = useState<string>("");
+    const [selected, setSelected] = useState<string[]>([]);
+    const [loading, setLoading] = useState<boolean>(false);
+    const [running, setRunning] = useState<boolean>(false);
+    const [output, setOutput] = useState<string[]>([]);
+    const outputRef = useRef<HTMLPreElement>(null);
+
+    useEffect(() => {
+        let unlisten: (() => void) | null = null;
+
+        const setupListener = async () => {
+            unlisten = await listen<string>("winetricks-output", (event) => {
+                setOutput((current) =>
+                    [...current, event.payload].slice(-MAX_OUTPUT_LINES),
+                );
+            });
+        };
+
+        setupListener();
+
+        return () => {
+            if (unlisten) unlisten();
+        };
+    }, []);
+
+    useEffect(() => {
+        outputRef.current?.scrollTo(0, outputRef.current.scrollHeight);
+    }, [output]);
+
+    if (!availableCommands.winetricks) {
+        return null;
+    }
+
+    const categories = [...new Set(verbs.map((v) => v.category))];
+    const shownVerbs = verbs.filter(
+        (v) =>
+            v.category === category &&
+            (v.name.includes(filter.toLowerCase()) ||
+                v.description.toLowerCase().includes(filter.toLowerCase())),
+    );
+
+    const handleLoadVerbs = async () => {
+        setLoading(true);
+        try {
+            setVerbs(await getWinetricksVerbs());
+        } catch (error) {
+            await message(String(error), {
+                title: "Something Went Wrong",
+                kind: "error",
+            });
+        } finally {
+            setLoading(false);
+        }
+    };
+
+    const handleToggleVerb = (name: string) => {
+        setSelected((current) =>
+            current.includes(name)
+                ? current.filter((v) => v !== name)
+                : [...current, name],
+        );
+    };
+
+    const handleInstall = async () => {
+        setRunning(true);
+        setOutput([]);
+        try {
+            const installed = await installWinetricksVerbs(selected);
+            setVerbs((current) =>
+                current.map((v) => ({
+                    ...v,
+                    installed: installed.includes(v.name),
+                })),
+            );
+            setSelected([]);
+        } catch (error) {
+            if (String(error) === "Operation cancelled") {
+                return;
+            }
+            await message(String(error), {
+                title: "Something Went Wrong",
+                kind: "error",
+            });
+        } finally {
+            setRunning(false);
+        }
+    };
+
+    const handleCancel = async () => {
+        try {
+            await cancelWinetricks();
+        } catch (error) {
+            await message(String(error), {
+                title: "Something Went Wrong",
+                kind: "error",
+            });
+        }
+    };
+
+    return (
+        <div className="flex flex-col gap-y-3 pl-6">
+            <h2 className="text-black dark:text-white font-bold -ml-6">
+                Winetricks
+            </h2>
+            {verbs.length === 0 ? (
+                <button
+                    onClick={handleLoadVerbs}
+                    disabled={loading}
+                    className="bg-white dark:bg-[#444] text-black dark:text-white w-fit px-3 py-1 rounded cursor-pointer disabled:pointer-events-none disabled:opacity-50"
+                >
+                    {loading ? "Loading verbs..." : "Load verbs"}
+                </button>
+            ) : (
+                <>
+                    <Select
+                        id="winetricks-category"
+                        label="Category"
+                        value={category}
+                        onChange={setCategory}
+                        options={categories.map((c) => ({
+                            label: c,
+                            value: c,
+                        }))}
+                    />
+                    <TextField
+                        id="winetricks-filter"
+                        label="Filter"
+                        type="text"
+                        value={filter}
+                        onChange={setFilter}
+                    />
+                    <div className="max-h-64 overflow-y-auto flex flex-col gap-y-1 text-black dark:text-white text-[15px]">
+                        {shownVerbs.map((v) => (
+                            <label
+                                key={v.name}
+                                className="flex items-center gap-x-2"
+                            >
+                                <input
+                                    type="checkbox"
+                                    checked={selected.includes(v.name)}
+                                    onChange={() => handleToggleVerb(v.name)}
+                                    disabled={running}
+                                />
+                                <span className="font-medium">{v.name}</span>
+                                <span className="opacity-70 truncate">
+                                    {v.description}
+                                </span>
+                                {v.installed && (
+                                    <span className="ml-auto opacity-70">
+                                        installed
+                                    </span>
+                                )}
+                            </label>
+                        ))}
+                    </div>
+                    <div className="flex items-center gap-x-4">
+                        <button
+                            onClick={handleInstall}
+                            disabled={running || selected.length === 0}
+                            className="bg-white dark:bg-[#444] text-black dark:text-white w-fit px-3 py-1 rounded cursor-pointer disabled:pointer-events-none disabled:opacity-50"
+                        >
+                            {running
+                                ? "Running winetricks..."
+                                : `Install selected (${selected.length})`}
+                        </button>
+                        {running && (
+                            <button
+                                onClick={handleCancel}
+                                className="bg-white dark:bg-[#444] text-black dark:text-white w-fit px-3 py-1 rounded cursor-pointer"
+                            >
+                                Cancel
+                            </button>
+                        )}
+                    </div>
+                </>
+            )}
+            {output.length > 0 && (
+                <pre
+                    ref={outputRef}
+                    className="max-h-48 overflow-y-auto p-2 rounded bg-[#ddd] dark:bg-[#222] text-black dark:text-white text-xs whitespace-pre-wrap"
+                >
+                    {output.join("\n")}
+                </pre>
+            )}
+        </div>
+    );
+};
+
+export default WinetricksComponent;
@@ -3,7 +3,7 @@ import { Link } from "react-router-dom";
 import { homeDir } from "@tauri-apps/api/path";
 import { open } from "@tauri-apps/plugin-dialog";
 import ProgressBar from "../../layouts/ProgressBar/ProgressBar";
-import { startSettingUp } from "../../api/tauri";
+import { cancelSetup, startSettingUp } from "../../api/tauri";
 import { WizardListener } from "./WizardListener";
 
 const SetupOptions = ({ onNext }: { onNext: (options: any) => void }) => {
@@ -13,6 +13,14 @@ const SetupOptions = ({ onNext }: { onNext: (options: any) => void }) => {
         useState<boolean>(false);
     const [menuShortcutChecked, setMenuShortcutChecked] =
         useState<boolean>(false);
+    const [resumeChecked, setResumeChecked] = useState<boolean>(false);
+    const [networkChecked, setNetworkChecked] = useState<boolean>(false);
+    const [apiBaseUrl, setApiBaseUrl] = useState<string>(
+        "https://api.github.com",
+    );
+    const [mirrorUrls, setMirrorUrls] = useState<string>("");
+    const [proxy, setProxy] = useState<string>("");
+    const [githubToken, setGithubToken] = useState<string>("");
 
     const handleGetWinePrefix = async () => {
         const newWinePrefix: string | null = await open({
@@ -27,7 +35,25 @@ const SetupOptions = ({ onNext }: { onNext: (options: any) => void }) => {
     };
 
     const handleSubmit = () => {
-        onNext({ winePrefix, desktopShortcutChecked, menuShortcutChecked });
+        //left out unless configured, so a re-setup keeps the existing network settings
+        const network: Partial<NetworkSettings> | undefined = networkChecked
+            ? {
+                  api_base_url: apiBaseUrl.trim() || "https://api.github.com",
+                  mirror_urls: mirrorUrls
+                      .split(/[\s,]+/)
+                      .filter((url) => url !== ""),
+                  proxy: proxy.trim() || null,
+                  github_token: githubToken.trim() || null,
+              }
+            : undefined;
+
+        onNext({
+            winePrefix,
+            desktopShortcutChecked,
+            menuShortcutChecked,
+            resumeChecked,
+            network,
+        });
     };
 
     useEffect(() => {
@@ -108,6 +134,76 @@ const SetupOptions = ({ onNext }: { onNext: (options: any) => void }) => {
                             />
                             <p>Create application menu shortcut</p>
                         </label>
+                        <label className="w-full flex items-center gap-x-2 text-black dark:text-white cursor-pointer">
+                            <input
+                                type="checkbox"
+                                checked={resumeChecked}
+                                onChange={() =>
+                                    setResumeChecked((prev) => !prev)
+                                }
+                            />
+                            <p>Resume previous unfinished setup</p>
+                        </label>
+                        <label className="w-full flex items-center gap-x-2 text-black dark:text-white cursor-pointer">
+                            <input
+                                type="checkbox"
+                                checked={networkChecked}
+                                onChange={() =>
+                                    setNetworkChecked((prev) => !prev)
+                                }
+                            />
+                            <p>
+                                Configure network (proxy, mirrors, GitHub
+                                token)
+                            </p>
+                        </label>
+                        {networkChecked && (
+                            <div className="w-full flex flex-col gap-y-2 pl-6">
+                                {[
+                                    {
+                                        label: "GitHub API URL",
+                                        value: apiBaseUrl,
+                                        onChange: setApiBaseUrl,
+                                        type: "text",
+                                    },
+                                    {
+                                        label: "Download mirrors",
+                                        value: mirrorUrls,
+                                        onChange: setMirrorUrls,
+                                        type: "text",
+                                    },
+                                    {
+                                        label: "Proxy",
+                                        value: proxy,
+                                        onChange: setProxy,
+                                        type: "text",
+                                    },
+                                    {
+                                        label: "GitHub token",
+                                        value: githubToken,
+                                        onChange: setGithubToken,
+                                        type: "password",
+                                    },
+                                ].map((field) => (
+                                    <label
+                                        key={field.label}
+                                        className="w-full flex items-center gap-x-3 text-black dark:text-white"
+                                    >
+                                        <p className="w-44 shrink-0">
+                                            {field.label}
+                                        </p>
+                                        <input
+                                            className="w-full text-white px-3 py-1 border border-gray-600 focus:outline-none focus:ring-2 focus:ring-white rounded bg-[#222] dark:bg-[#111]"
+                                            type={field.type}
+                                            value={field.value}
+                                            onChange={(e) =>
+                                                field.onChange(e.target.value)
+                                            }
+                                        />
+                                    </label>
+                                ))}
+                            </div>
+                        )}
                     </div>
                     {/*<div className="w-full flex">
                         <p className="text-primary">Warning</p>: Couldn't find
@@ -175,6 +271,14 @@ const Installation = ({ options }: { options: any }) => {
                     <p className="text-black dark:text-white">{step}</p>
                     <ProgressBar percentage={progressPercentage} />
                 </div>
+                <div className="w-full flex items-center justify-end">
+                    <button
+                        onClick={() => cancelSetup()}
+                        className="button px-5 py-2 rounded-md font-medium shadow-md dark:shadow-none cursor-pointer"
+                    >
+                        Cancel
+                    </button>
+                </div>
             </div>
             <div></div>
             <WizardListener
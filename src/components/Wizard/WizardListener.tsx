@@ -16,6 +16,7 @@ const messages: Record<string, string> = {
     ValidateInstallation: "Validating installation...",
     Complete: "Setup completed successfully!",
     Failed: "Setup failed, rerun the setup",
+    Cancelled: "Setup cancelled",
 };
 
 const stepProgress: Record<string, number> = {
@@ -31,6 +32,29 @@ const stepProgress: Record<string, number> = {
     ValidateInstallation: 95,
     Complete: 100,
     Failed: 100,
+    Cancelled: 100,
+};
+
+const toMegabytes = (bytes: number) => (bytes / (1024 * 1024)).toFixed(1);
+
+const formatDownloadProgress = (progress: IDownloadProgress) => {
+    const total =
+        progress.total_bytes !== null
+            ? ` / ${toMegabytes(progress.total_bytes)}`
+            : "";
+    const eta =
+        progress.eta_seconds !== null ? `, ${progress.eta_seconds}s left` : "";
+
+    return `Downloading ${progress.file_name}... ${toMegabytes(progress.downloaded_bytes)}${total} MB (${toMegabytes(progress.bytes_per_second)} MB/s${eta})`;
+};
+
+const formatExtractProgress = (progress: IExtractProgress) => {
+    const percent =
+        progress.total_bytes > 0
+            ? Math.floor((progress.processed_bytes / progress.total_bytes) * 100)
+            : 0;
+
+    return `Extracting ${progress.file_name}... ${percent}% (${progress.entries_extracted} files)`;
 };
 
 export const WizardListener = ({
@@ -43,6 +67,8 @@ export const WizardListener = ({
     const navigate = useNavigate();
     useEffect(() => {
         let unlistenStep: (() => void) | null = null;
+        let unlistenDownload: (() => void) | null = null;
+        let unlistenExtract: (() => void) | null = null;
 
         const setupListeners = async () => {
             unlistenStep = await listen<string>(
@@ -65,15 +91,37 @@ export const WizardListener = ({
                         }).then(() => {
                             navigate("/");
                         });
+                    } else if (step === "Cancelled") {
+                        await message(
+                            "Setup cancelled, it can be resumed later",
+                        ).then(() => {
+                            navigate("/");
+                        });
                     }
                 },
             );
+
+            unlistenDownload = await listen<IDownloadProgress>(
+                "download-progress",
+                (event) => {
+                    onStepUpdate(formatDownloadProgress(event.payload));
+                },
+            );
+
+            unlistenExtract = await listen<IExtractProgress>(
+                "extract-progress",
+                (event) => {
+                    onStepUpdate(formatExtractProgress(event.payload));
+                },
+            );
         };
 
         setupListeners();
 
         return () => {
             if (unlistenStep) unlistenStep();
+            if (unlistenDownload) unlistenDownload();
+            if (unlistenExtract) unlistenExtract();
         };
     }, []);
 
@@ -23,9 +23,20 @@ interface IKeyValueEditorProps {
 /*
 interfaces for api
 */
+type RunnerOrigin =
+    | "Utility"
+    | "Lutris"
+    | "Steam"
+    | "Bottles"
+    | "Heroic"
+    | "Custom"
+    | "System";
+
 interface IVersions {
     name: string;
     path: string;
+    version?: string | null;
+    origin?: RunnerOrigin;
 }
 
 type WineSettings = {
@@ -36,10 +47,21 @@ type WineSettings = {
     feral_gamemode: boolean;
 };
 
+type DXVKInstallMode = "symlink" | "copy";
+
 type DXVKSettings = {
     version: string;
     path: string;
     enabled: boolean;
+    install_mode: DXVKInstallMode;
+};
+
+type D3D9Backend = "wined3d" | "dxvk" | "nine";
+
+type D3D9Settings = {
+    backend: D3D9Backend;
+    nine_version: string;
+    nine_path: string;
 };
 
 type GameSettings = {
@@ -49,6 +71,7 @@ type GameSettings = {
     mangohud: boolean;
     environment_variables: IKeyValue[];
     dll_overrides: IKeyValue[];
+    persist_dll_overrides: boolean;
 };
 
 type SettingsState = {
@@ -59,15 +82,157 @@ type SettingsState = {
     availableDXVKVersions?: IVersions[];
 };
 
+type CacheSettings = {
+    max_size_mb: number;
+};
+
+type NetworkSettings = {
+    api_base_url: string;
+    mirror_urls: string[];
+    proxy: string | null;
+    github_token: string | null;
+    connect_timeout_secs: number;
+    read_timeout_secs: number;
+    max_retries: number;
+};
+
+type RunnerSource = {
+    name: string;
+    repo: string;
+    kind: "Wine" | "Proton";
+    asset_suffix: string;
+};
+
+type DXVKSource = {
+    name: string;
+    repo: string;
+    asset_suffix: string;
+};
+
+type SearchPathsSettings = {
+    auto_detect: boolean;
+    runner_paths: string[];
+    dxvk_paths: string[];
+};
+
+type RunnersSettings = {
+    sources: RunnerSource[];
+    dxvk_sources: DXVKSource[];
+    releases_per_source: number;
+};
+
 interface IPopulateSettings {
     wine: WineSettings;
     dxvk: DXVKSettings;
     game: GameSettings;
+    cache?: CacheSettings;
+    network?: NetworkSettings;
+    runners?: RunnersSettings;
+    search_paths?: SearchPathsSettings;
+    d3d9?: D3D9Settings;
     available_wine_versions?: IVersions[];
     available_dxvk_versions?: IVersions[];
 }
 
+interface IPrefixDXVKState {
+    installed: boolean;
+    version: string | null;
+    path: string | null;
+    install_mode: DXVKInstallMode;
+    updated_at: number;
+}
+
+type WindowsVersion =
+    | "win11"
+    | "win10"
+    | "win81"
+    | "win8"
+    | "win7"
+    | "vista"
+    | "winxp";
+
+type AudioDriver = "auto" | "pulse" | "alsa" | "oss" | "disabled";
+
+type MouseWarpOverride = "enable" | "disable" | "force";
+
+interface IPrefixSettings {
+    windows_version: WindowsVersion;
+    virtual_desktop: boolean;
+    desktop_width: number;
+    desktop_height: number;
+    audio_driver: AudioDriver;
+    mouse_warp_override: MouseWarpOverride;
+}
+
 interface IAvailableCommands {
     mangohud: boolean;
     gamemoderun: boolean;
+    winetricks?: boolean;
+}
+
+interface IWinetricksVerb {
+    name: string;
+    category: string;
+    description: string;
+    installed: boolean;
+}
+
+interface IDownloadProgress {
+    file_name: string;
+    downloaded_bytes: number;
+    total_bytes: number | null;
+    bytes_per_second: number;
+    eta_seconds: number | null;
+}
+
+interface IExtractProgress {
+    file_name: string;
+    entry: string;
+    entries_extracted: number;
+    processed_bytes: number;
+    total_bytes: number;
+}
+
+interface ICatalogRelease {
+    source: string;
+    tag_name: string;
+    asset_name: string;
+    download_url: string;
+    size: number;
+    published_at: string | null;
+    prerelease: boolean;
+    installed: boolean;
+}
+
+interface IRunnerRelease extends ICatalogRelease {
+    kind: "Wine" | "Proton";
+    digest: string | null;
+}
+
+interface ICacheEntry {
+    url: string;
+    file_name: string;
+    sha256: string;
+    size: number;
+    last_used: number;
+}
+
+interface ICacheInfo {
+    path: string;
+    total_size: number;
+    max_size: number;
+    entries: ICacheEntry[];
+}
+
+interface IPrefixSnapshot {
+    id: string;
+    created_at: number;
+    prefix: string;
+    runner: string;
+    runner_path: string;
+    dxvk_version: string;
+    dxvk_enabled: boolean;
+    d3d9_backend: D3D9Backend;
+    include_game_files: boolean;
+    size: number;
 }